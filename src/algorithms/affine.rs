@@ -0,0 +1,281 @@
+//! Affine transforms of the active input, drawn as a ghost over the original. The parameters are
+//! shared with the on-map gizmo in [`crate::gizmo`], so dragging its handles moves the sliders.
+
+use std::sync::{Arc, RwLock};
+
+use super::{
+    Algorithm, AlgorithmOutput, DocExample, InputScene, active_in_working_crs, active_with_scale,
+    binding_name, from_working_crs, outline_overlays,
+};
+use egui::{Color32, Ui};
+use geo::{
+    AffineOps, AffineTransform, Centroid as _, Coord, CoordsIter, Geometry, Point, Rotate, Scale,
+    Skew, Translate,
+};
+
+/// Point that rotation, scaling and skewing happen around.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TransformOrigin {
+    /// The centroid of the active input in the working CRS.
+    Centroid,
+    /// A fixed lon/lat, set by dragging the gizmo's origin handle.
+    Custom(Coord<f64>),
+}
+
+#[derive(Debug, Clone)]
+pub struct AffineParams {
+    pub origin: TransformOrigin,
+    /// Counter-clockwise rotation in degrees.
+    pub angle: f64,
+    pub scale_x: f64,
+    pub scale_y: f64,
+    pub translate_x_meters: f64,
+    pub translate_y_meters: f64,
+    /// Skew angles in degrees.
+    pub skew_x: f64,
+    pub skew_y: f64,
+    pub show_gizmo: bool,
+}
+
+impl Default for AffineParams {
+    fn default() -> Self {
+        AffineParams {
+            origin: TransformOrigin::Centroid,
+            angle: 30.0,
+            scale_x: 1.5,
+            scale_y: 1.5,
+            translate_x_meters: 20_000.0,
+            translate_y_meters: 10_000.0,
+            skew_x: 15.0,
+            skew_y: 0.0,
+            show_gizmo: true,
+        }
+    }
+}
+
+impl AffineParams {
+    /// The transform origin in the working CRS.
+    pub fn origin_in_working_crs(&self, scene: &InputScene) -> Option<Coord<f64>> {
+        match self.origin {
+            TransformOrigin::Centroid => active_in_working_crs(scene)
+                .ok()?
+                .centroid()
+                .map(|point| point.0),
+            TransformOrigin::Custom(lonlat) => scene.working_crs.forward(lonlat),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AffineKind {
+    Rotate,
+    Scale,
+    Translate,
+    Skew,
+    /// All of the above composed into one `AffineTransform`: scale, skew, rotate, then translate.
+    Combined,
+}
+
+pub struct AffineTransformAlgorithm {
+    kind: AffineKind,
+    params: Arc<RwLock<AffineParams>>,
+}
+
+impl AffineTransformAlgorithm {
+    pub fn new(kind: AffineKind, params: Arc<RwLock<AffineParams>>) -> Self {
+        AffineTransformAlgorithm { kind, params }
+    }
+
+    fn color(&self) -> Color32 {
+        match self.kind {
+            AffineKind::Rotate => Color32::from_rgb(65, 105, 225),
+            AffineKind::Scale => Color32::from_rgb(46, 139, 87),
+            AffineKind::Translate => Color32::from_rgb(205, 92, 92),
+            AffineKind::Skew => Color32::from_rgb(218, 165, 32),
+            AffineKind::Combined => Color32::from_rgb(105, 105, 105),
+        }
+    }
+
+    fn transform(
+        &self,
+        geometry: &Geometry<f64>,
+        origin: Coord<f64>,
+        units_per_meter: f64,
+    ) -> Geometry<f64> {
+        let params = self.params.read().unwrap();
+        let translate_x = params.translate_x_meters * units_per_meter;
+        let translate_y = params.translate_y_meters * units_per_meter;
+        match self.kind {
+            AffineKind::Rotate => geometry.rotate_around_point(params.angle, Point(origin)),
+            AffineKind::Scale => {
+                geometry.scale_around_point(params.scale_x, params.scale_y, origin)
+            }
+            AffineKind::Translate => geometry.translate(translate_x, translate_y),
+            AffineKind::Skew => geometry.skew_around_point(params.skew_x, params.skew_y, origin),
+            AffineKind::Combined => {
+                let transform = AffineTransform::scale(params.scale_x, params.scale_y, origin)
+                    .skewed(params.skew_x, params.skew_y, origin)
+                    .rotated(params.angle, origin)
+                    .translated(translate_x, translate_y);
+                geometry.affine_transform(&transform)
+            }
+        }
+    }
+
+    // The active input in the working CRS, the transform origin, the working CRS units per meter
+    // and the transformed input, shared by the output and the snippet.
+    fn compute(
+        &self,
+        scene: &InputScene,
+    ) -> Result<(Geometry<f64>, Coord<f64>, f64, Geometry<f64>), String> {
+        let (geometry, units_per_meter) = active_with_scale(scene)?;
+        let origin = self
+            .params
+            .read()
+            .unwrap()
+            .origin_in_working_crs(scene)
+            .ok_or_else(|| "The transform origin is undefined.".to_string())?;
+        let transformed = self.transform(&geometry, origin, units_per_meter);
+        Ok((geometry, origin, units_per_meter, transformed))
+    }
+}
+
+impl Algorithm for AffineTransformAlgorithm {
+    fn name(&self) -> String {
+        match self.kind {
+            AffineKind::Rotate => "Rotate",
+            AffineKind::Scale => "Scale",
+            AffineKind::Translate => "Translate",
+            AffineKind::Skew => "Skew",
+            AffineKind::Combined => "Affine Transform",
+        }
+        .to_string()
+    }
+
+    fn calculate_and_box_output(&mut self, scene: &InputScene) -> Option<AlgorithmOutput> {
+        let (_, origin, _, transformed) = match self.compute(scene) {
+            Ok(computed) => computed,
+            Err(message) => return Some(AlgorithmOutput::text(message)),
+        };
+
+        let text = match scene.working_crs.inverse(origin) {
+            Some(origin) => format!("around {:.6}, {:.6}", origin.x, origin.y),
+            None => "around an origin outside the working CRS".to_string(),
+        };
+        let overlays = from_working_crs(&transformed, &scene.working_crs)
+            .map(|transformed| outline_overlays(&transformed, self.color().gamma_multiply(0.6)))
+            .unwrap_or_default();
+        Some(AlgorithmOutput::text(text).with_overlays(overlays))
+    }
+
+    fn has_doc_example(&self) -> bool {
+        true
+    }
+
+    /// The transform as `geo` calls, asserting on the first transformed coordinate.
+    fn doc_example(&self, scene: &InputScene) -> Result<String, String> {
+        let (geometry, origin, units_per_meter, transformed) = self.compute(scene)?;
+        let params = self.params.read().unwrap().clone();
+        let first = transformed
+            .coords_iter()
+            .next()
+            .ok_or_else(|| "The geometry is empty.".to_string())?;
+        let translate_x = params.translate_x_meters * units_per_meter;
+        let translate_y = params.translate_y_meters * units_per_meter;
+
+        let name = binding_name(&geometry);
+        let mut example = DocExample::default()
+            .import("CoordsIter")
+            .in_working_crs(&scene.working_crs)
+            .geometry(name, geometry.clone());
+        if self.kind != AffineKind::Translate {
+            example = example.geometry("origin", Point(origin));
+        }
+        let (import, call) = match self.kind {
+            AffineKind::Rotate => (
+                "Rotate",
+                format!("{}.rotate_around_point({:?}, origin)", name, params.angle),
+            ),
+            AffineKind::Scale => (
+                "Scale",
+                format!(
+                    "{}.scale_around_point({:?}, {:?}, origin)",
+                    name, params.scale_x, params.scale_y
+                ),
+            ),
+            AffineKind::Translate => (
+                "Translate",
+                format!("{}.translate({:?}, {:?})", name, translate_x, translate_y),
+            ),
+            AffineKind::Skew => (
+                "Skew",
+                format!(
+                    "{}.skew_around_point({:?}, {:?}, origin)",
+                    name, params.skew_x, params.skew_y
+                ),
+            ),
+            AffineKind::Combined => {
+                example = example.import("AffineTransform").line(format!(
+                    "let transform = AffineTransform::scale({:?}, {:?}, origin)\n    .skewed({:?}, {:?}, origin)\n    .rotated({:?}, origin)\n    .translated({:?}, {:?});",
+                    params.scale_x,
+                    params.scale_y,
+                    params.skew_x,
+                    params.skew_y,
+                    params.angle,
+                    translate_x,
+                    translate_y
+                ));
+                (
+                    "AffineOps",
+                    format!("{}.affine_transform(&transform)", name),
+                )
+            }
+        };
+        Ok(example
+            .import(import)
+            .line(format!("let transformed = {};", call))
+            .line("let first = transformed.coords_iter().next().unwrap();")
+            .assert_close("first.x", first.x)
+            .assert_close("first.y", first.y)
+            .render())
+    }
+
+    fn settings_ui(&mut self, ui: &mut Ui) {
+        let mut params = self.params.write().unwrap();
+        let params = &mut *params;
+        match self.kind {
+            AffineKind::Rotate => angle_slider(ui, &mut params.angle),
+            AffineKind::Scale => scale_sliders(ui, params),
+            AffineKind::Translate => translate_sliders(ui, params),
+            AffineKind::Skew => skew_sliders(ui, params),
+            AffineKind::Combined => {
+                ui.label("Uses the parameters of the four transforms above.");
+            }
+        }
+    }
+}
+
+fn angle_slider(ui: &mut Ui, angle: &mut f64) {
+    ui.add(egui::Slider::new(angle, -180.0..=180.0).text("Angle (°)"));
+}
+
+fn scale_sliders(ui: &mut Ui, params: &mut AffineParams) {
+    ui.add(egui::Slider::new(&mut params.scale_x, -3.0..=3.0).text("Scale x"));
+    ui.add(egui::Slider::new(&mut params.scale_y, -3.0..=3.0).text("Scale y"));
+}
+
+fn translate_sliders(ui: &mut Ui, params: &mut AffineParams) {
+    ui.add(
+        egui::Slider::new(&mut params.translate_x_meters, -100_000.0..=100_000.0)
+            .text("Translate x (m)"),
+    );
+    ui.add(
+        egui::Slider::new(&mut params.translate_y_meters, -100_000.0..=100_000.0)
+            .text("Translate y (m)"),
+    );
+}
+
+fn skew_sliders(ui: &mut Ui, params: &mut AffineParams) {
+    ui.add(egui::Slider::new(&mut params.skew_x, -60.0..=60.0).text("Skew x (°)"));
+    ui.add(egui::Slider::new(&mut params.skew_y, -60.0..=60.0).text("Skew y (°)"));
+}
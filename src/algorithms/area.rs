@@ -0,0 +1,193 @@
+use super::{Algorithm, AlgorithmOutput, DocExample, InputScene, to_working_crs};
+use crate::crs::WorkingCrs;
+use egui::Ui;
+use geo::{
+    Area, ChamberlainDuquetteArea, Euclidean, GeodesicArea, Haversine, Length, LineString, Polygon,
+};
+use std::fmt::{self, Display};
+
+/// Area and perimeter of every polygon input, computed with each of geo's methods side by side.
+///
+/// The planar row runs in the working CRS, so switching it shows how much each projection
+/// distorts area. Chamberlain-Duquette only gives an area and Haversine only a perimeter.
+pub struct AreaComparison;
+
+struct AreaRow {
+    method: String,
+    area: Option<String>,
+    signed_area: Option<String>,
+    perimeter: Option<String>,
+}
+
+struct AreaTable {
+    polygons: Vec<(String, Vec<AreaRow>)>,
+}
+
+impl Display for AreaTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, (name, rows)) in self.polygons.iter().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            writeln!(f, "{}", name)?;
+            writeln!(
+                f,
+                "{:<28}{:>16}{:>16}{:>14}",
+                "Method", "Area", "Signed area", "Perimeter"
+            )?;
+            for row in rows {
+                let cell =
+                    |value: &Option<String>| value.clone().unwrap_or_else(|| "-".to_string());
+                writeln!(
+                    f,
+                    "{:<28}{:>16}{:>16}{:>14}",
+                    row.method,
+                    cell(&row.area),
+                    cell(&row.signed_area),
+                    cell(&row.perimeter)
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl AreaComparison {
+    fn rows_for(&self, polygon: &Polygon<f64>, working_crs: &WorkingCrs) -> Vec<AreaRow> {
+        let planar = match to_working_crs(polygon, working_crs) {
+            Some(projected) => {
+                let metric = working_crs.is_metric();
+                AreaRow {
+                    method: format!("Planar ({})", working_crs),
+                    area: Some(format_area(projected.unsigned_area(), metric)),
+                    signed_area: Some(format_area(projected.signed_area(), metric)),
+                    perimeter: Some(format_length(
+                        rings(&projected).map(|ring| ring.length(&Euclidean)).sum(),
+                        metric,
+                    )),
+                }
+            }
+            None => AreaRow {
+                method: format!("Planar ({})", working_crs),
+                area: Some("outside CRS".to_string()),
+                signed_area: None,
+                perimeter: None,
+            },
+        };
+        let (geodesic_area, geodesic_perimeter) = geodesic(polygon);
+
+        vec![
+            planar,
+            AreaRow {
+                method: "Chamberlain-Duquette".to_string(),
+                area: Some(format_area(
+                    polygon.chamberlain_duquette_unsigned_area(),
+                    true,
+                )),
+                signed_area: Some(format_area(
+                    polygon.chamberlain_duquette_signed_area(),
+                    true,
+                )),
+                perimeter: None,
+            },
+            AreaRow {
+                method: "Geodesic".to_string(),
+                area: Some(format_area(geodesic_area, true)),
+                signed_area: Some(format_area(polygon.geodesic_area_signed(), true)),
+                perimeter: Some(format_length(geodesic_perimeter, true)),
+            },
+            AreaRow {
+                method: "Haversine".to_string(),
+                area: None,
+                signed_area: None,
+                perimeter: Some(format_length(
+                    rings(polygon).map(|ring| ring.length(&Haversine)).sum(),
+                    true,
+                )),
+            },
+        ]
+    }
+}
+
+impl Algorithm for AreaComparison {
+    fn name(&self) -> String {
+        "Area and Perimeter".to_string()
+    }
+
+    fn calculate_and_box_output(&mut self, scene: &InputScene) -> Option<AlgorithmOutput> {
+        let polygons: Vec<(String, Vec<AreaRow>)> = scene
+            .polygons()
+            .map(|(name, polygon)| (name.to_string(), self.rows_for(polygon, &scene.working_crs)))
+            .collect();
+        if polygons.is_empty() {
+            return Some(AlgorithmOutput::text(
+                "Area and Perimeter: Requires a polygon input.",
+            ));
+        }
+        Some(AlgorithmOutput::text(AreaTable { polygons }))
+    }
+
+    fn has_doc_example(&self) -> bool {
+        true
+    }
+
+    /// The geodesic area and perimeter of the first polygon, which are the most accurate rows.
+    fn doc_example(&self, scene: &InputScene) -> Result<String, String> {
+        let (_, polygon) = scene
+            .polygons()
+            .next()
+            .ok_or_else(|| "Requires a polygon input.".to_string())?;
+        let (area, perimeter) = geodesic(polygon);
+        Ok(DocExample::default()
+            .import("GeodesicArea")
+            .geometry("polygon", polygon.clone())
+            .line("let area = polygon.geodesic_area_unsigned();")
+            .line("let perimeter = polygon.geodesic_perimeter();")
+            .assert_close("area", area)
+            .assert_close("perimeter", perimeter)
+            .render())
+    }
+
+    fn display_ui(&self, ui: &mut Ui, output: &Option<AlgorithmOutput>) {
+        ui.label(format!("{}:", self.name()));
+        let content = output
+            .as_ref()
+            .map_or_else(|| "N/A".to_string(), |val| val.text.to_string());
+        ui.monospace(content);
+    }
+}
+
+// Unsigned geodesic area and perimeter, shared by the table and the snippet.
+fn geodesic(polygon: &Polygon<f64>) -> (f64, f64) {
+    (
+        polygon.geodesic_area_unsigned(),
+        polygon.geodesic_perimeter(),
+    )
+}
+
+// Exterior ring followed by the holes. Hole lengths count towards the perimeter.
+fn rings(polygon: &Polygon<f64>) -> impl Iterator<Item = &LineString<f64>> {
+    std::iter::once(polygon.exterior()).chain(polygon.interiors())
+}
+
+// Square meters below one square kilometer, square kilometers above. Non-metric values are in
+// square degrees.
+fn format_area(value: f64, metric: bool) -> String {
+    if !metric {
+        format!("{:.6} deg²", value)
+    } else if value.abs() < 1e6 {
+        format!("{:.1} m²", value)
+    } else {
+        format!("{:.3} km²", value / 1e6)
+    }
+}
+
+fn format_length(value: f64, metric: bool) -> String {
+    if !metric {
+        format!("{:.6}°", value)
+    } else if value.abs() < 1e3 {
+        format!("{:.1} m", value)
+    } else {
+        format!("{:.3} km", value / 1e3)
+    }
+}
@@ -0,0 +1,137 @@
+//! Boolean operations between two polygon inputs, A and B.
+
+use super::{
+    Algorithm, AlgorithmOutput, DocExample, InputScene, multi_polygon_overlays, to_working_crs,
+};
+use egui::Color32;
+use geo::{Area, BooleanOps, Geometry, MultiPolygon, Polygon};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BooleanOperation {
+    Intersection,
+    Union,
+    /// A − B
+    Difference,
+    /// B − A
+    ReverseDifference,
+    Xor,
+}
+
+pub struct BooleanOp {
+    operation: BooleanOperation,
+}
+
+impl BooleanOp {
+    pub fn new(operation: BooleanOperation) -> Self {
+        BooleanOp { operation }
+    }
+
+    fn color(&self) -> Color32 {
+        match self.operation {
+            BooleanOperation::Intersection => Color32::from_rgb(255, 215, 0),
+            BooleanOperation::Union => Color32::from_rgb(65, 105, 225),
+            BooleanOperation::Difference => Color32::from_rgb(220, 20, 60),
+            BooleanOperation::ReverseDifference => Color32::from_rgb(34, 139, 34),
+            BooleanOperation::Xor => Color32::from_rgb(148, 0, 211),
+        }
+    }
+
+    fn apply(&self, a: &Polygon<f64>, b: &Polygon<f64>) -> MultiPolygon<f64> {
+        match self.operation {
+            BooleanOperation::Intersection => a.intersection(b),
+            BooleanOperation::Union => a.union(b),
+            BooleanOperation::Difference => a.difference(b),
+            BooleanOperation::ReverseDifference => b.difference(a),
+            BooleanOperation::Xor => a.xor(b),
+        }
+    }
+
+    /// The names of A and B, and the polygons in the working CRS. A and B are the active and the
+    /// second input when both are distinct polygons, and the first two polygon inputs otherwise.
+    fn polygons<'a>(
+        &self,
+        scene: &'a InputScene,
+    ) -> Result<((&'a str, &'a str), Polygon<f64>, Polygon<f64>), String> {
+        let ((a_name, a), (b_name, b)) = scene
+            .pair_of(|geometry| match geometry {
+                Geometry::Polygon(polygon) => Some(polygon),
+                _ => None,
+            })
+            .ok_or_else(|| "Requires two polygon inputs.".to_string())?;
+        match (
+            to_working_crs(a, &scene.working_crs),
+            to_working_crs(b, &scene.working_crs),
+        ) {
+            (Some(a), Some(b)) => Ok(((a_name, b_name), a, b)),
+            _ => Err(format!("Polygons are outside {}.", scene.working_crs)),
+        }
+    }
+}
+
+impl Algorithm for BooleanOp {
+    fn name(&self) -> String {
+        match self.operation {
+            BooleanOperation::Intersection => "Intersection (A ∩ B)",
+            BooleanOperation::Union => "Union (A ∪ B)",
+            BooleanOperation::Difference => "Difference (A − B)",
+            BooleanOperation::ReverseDifference => "Difference (B − A)",
+            BooleanOperation::Xor => "XOR (A ⊕ B)",
+        }
+        .to_string()
+    }
+
+    fn calculate_and_box_output(&mut self, scene: &InputScene) -> Option<AlgorithmOutput> {
+        let ((a_name, b_name), a, b) = match self.polygons(scene) {
+            Ok(polygons) => polygons,
+            Err(message) => return Some(AlgorithmOutput::text(message)),
+        };
+
+        let result = self.apply(&a, &b);
+        let text = format!(
+            "A = {}, B = {}: {} polygons, {} rings, area {:.2} {}²",
+            a_name,
+            b_name,
+            result.0.len(),
+            result
+                .iter()
+                .map(|polygon| 1 + polygon.interiors().len())
+                .sum::<usize>(),
+            result.unsigned_area(),
+            scene.working_crs.unit_suffix()
+        );
+        Some(
+            AlgorithmOutput::text(text).with_overlays(multi_polygon_overlays(
+                &result,
+                &scene.working_crs,
+                self.color(),
+            )),
+        )
+    }
+
+    fn has_doc_example(&self) -> bool {
+        true
+    }
+
+    fn doc_example(&self, scene: &InputScene) -> Result<String, String> {
+        let ((a_name, b_name), a, b) = self.polygons(scene)?;
+        let result = self.apply(&a, &b);
+        let call = match self.operation {
+            BooleanOperation::Intersection => "a.intersection(&b)",
+            BooleanOperation::Union => "a.union(&b)",
+            BooleanOperation::Difference => "a.difference(&b)",
+            BooleanOperation::ReverseDifference => "b.difference(&a)",
+            BooleanOperation::Xor => "a.xor(&b)",
+        };
+        Ok(DocExample::default()
+            .import("Area")
+            .import("BooleanOps")
+            .in_working_crs(&scene.working_crs)
+            .line(format!("// a is {}, b is {}.", a_name, b_name))
+            .geometry("a", a)
+            .geometry("b", b)
+            .line(format!("let result = {};", call))
+            .assert_eq("result.0.len()", result.0.len())
+            .assert_close("result.unsigned_area()", result.unsigned_area())
+            .render())
+    }
+}
@@ -0,0 +1,251 @@
+//! Buffers and offset curves of the active input. Distances are entered in meters and converted to
+//! working CRS units at the input's centroid.
+
+use super::{
+    Algorithm, AlgorithmOutput, DocExample, InputScene, active_with_scale, binding_name,
+    from_working_crs, multi_polygon_overlays,
+};
+use crate::overlay::Overlay;
+use egui::{Color32, Ui};
+use geo::{
+    Area, Buffer as _, BufferStyle, Geometry, LineCap, LineJoin, LineString, MultiPolygon,
+    OffsetCurve as _,
+};
+
+const BUFFER_COLOR: Color32 = Color32::from_rgb(0, 128, 128);
+const OFFSET_CURVE_COLOR: Color32 = Color32::from_rgb(199, 21, 133);
+
+/// Approximation parameter passed to round caps and joins.
+const ROUND_ARC: f64 = 0.1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CapStyle {
+    Round,
+    Square,
+    Butt,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JoinStyle {
+    Round,
+    Miter,
+    Bevel,
+}
+
+pub struct Buffer {
+    pub distance_meters: f64,
+    pub cap: CapStyle,
+    pub join: JoinStyle,
+    /// Largest ratio of miter length to buffer distance before a miter join is beveled.
+    pub miter_limit: f64,
+}
+
+impl Default for Buffer {
+    fn default() -> Self {
+        Buffer {
+            distance_meters: 5_000.0,
+            cap: CapStyle::Round,
+            join: JoinStyle::Round,
+            miter_limit: 2.0,
+        }
+    }
+}
+
+impl Buffer {
+    fn style(&self, distance: f64) -> BufferStyle<f64> {
+        let cap = match self.cap {
+            CapStyle::Round => LineCap::Round(ROUND_ARC),
+            CapStyle::Square => LineCap::Square,
+            CapStyle::Butt => LineCap::Butt,
+        };
+        let join = match self.join {
+            JoinStyle::Round => LineJoin::Round(ROUND_ARC),
+            JoinStyle::Miter => LineJoin::Miter(self.miter_limit),
+            JoinStyle::Bevel => LineJoin::Bevel,
+        };
+        BufferStyle::new(distance).line_cap(cap).line_join(join)
+    }
+
+    /// [`Buffer::style`] as Rust source.
+    fn style_code(&self, distance: f64) -> String {
+        let cap = match self.cap {
+            CapStyle::Round => format!("LineCap::Round({:?})", ROUND_ARC),
+            CapStyle::Square => "LineCap::Square".to_string(),
+            CapStyle::Butt => "LineCap::Butt".to_string(),
+        };
+        let join = match self.join {
+            JoinStyle::Round => format!("LineJoin::Round({:?})", ROUND_ARC),
+            JoinStyle::Miter => format!("LineJoin::Miter({:?})", self.miter_limit),
+            JoinStyle::Bevel => "LineJoin::Bevel".to_string(),
+        };
+        format!(
+            "BufferStyle::new({:?})\n    .line_cap({})\n    .line_join({})",
+            distance, cap, join
+        )
+    }
+
+    // The active input in the working CRS, the distance in working CRS units and the buffer,
+    // shared by the output and the snippet.
+    fn compute(
+        &self,
+        scene: &InputScene,
+    ) -> Result<(Geometry<f64>, f64, MultiPolygon<f64>), String> {
+        let (geometry, units_per_meter) = active_with_scale(scene)?;
+        let distance = self.distance_meters * units_per_meter;
+        let buffered = geometry.buffer_with_style(self.style(distance));
+        Ok((geometry, distance, buffered))
+    }
+}
+
+impl Algorithm for Buffer {
+    fn name(&self) -> String {
+        "Buffer".to_string()
+    }
+
+    fn calculate_and_box_output(&mut self, scene: &InputScene) -> Option<AlgorithmOutput> {
+        let (_, _, buffered) = match self.compute(scene) {
+            Ok(computed) => computed,
+            Err(message) => return Some(AlgorithmOutput::text(message)),
+        };
+
+        let text = format!(
+            "{} polygons, {} holes, area {:.2} {}²",
+            buffered.0.len(),
+            buffered
+                .iter()
+                .map(|polygon| polygon.interiors().len())
+                .sum::<usize>(),
+            buffered.unsigned_area(),
+            scene.working_crs.unit_suffix()
+        );
+        Some(
+            AlgorithmOutput::text(text).with_overlays(multi_polygon_overlays(
+                &buffered,
+                &scene.working_crs,
+                BUFFER_COLOR,
+            )),
+        )
+    }
+
+    fn has_doc_example(&self) -> bool {
+        true
+    }
+
+    fn doc_example(&self, scene: &InputScene) -> Result<String, String> {
+        let (geometry, distance, buffered) = self.compute(scene)?;
+        let name = binding_name(&geometry);
+        Ok(DocExample::default()
+            .import("Area")
+            .import("Buffer")
+            .import("BufferStyle")
+            .import("LineCap")
+            .import("LineJoin")
+            .in_working_crs(&scene.working_crs)
+            .geometry(name, geometry.clone())
+            .line(format!("let style = {};", self.style_code(distance)))
+            .line(format!("let buffered = {}.buffer_with_style(style);", name))
+            .assert_eq("buffered.0.len()", buffered.0.len())
+            .assert_close("buffered.unsigned_area()", buffered.unsigned_area())
+            .render())
+    }
+
+    fn settings_ui(&mut self, ui: &mut Ui) {
+        ui.add(
+            egui::Slider::new(&mut self.distance_meters, -50_000.0..=50_000.0).text("Distance (m)"),
+        );
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_salt("buffer_cap")
+                .selected_text(format!("Cap: {:?}", self.cap))
+                .show_ui(ui, |ui| {
+                    for cap in [CapStyle::Round, CapStyle::Square, CapStyle::Butt] {
+                        ui.selectable_value(&mut self.cap, cap, format!("{:?}", cap));
+                    }
+                });
+            egui::ComboBox::from_id_salt("buffer_join")
+                .selected_text(format!("Join: {:?}", self.join))
+                .show_ui(ui, |ui| {
+                    for join in [JoinStyle::Round, JoinStyle::Miter, JoinStyle::Bevel] {
+                        ui.selectable_value(&mut self.join, join, format!("{:?}", join));
+                    }
+                });
+        });
+        if self.join == JoinStyle::Miter {
+            ui.add(egui::Slider::new(&mut self.miter_limit, 1.0..=10.0).text("Miter limit"));
+        }
+    }
+}
+
+/// A copy of a line string shifted sideways. Positive distances offset to the left.
+pub struct OffsetCurve {
+    pub distance_meters: f64,
+}
+
+impl Default for OffsetCurve {
+    fn default() -> Self {
+        OffsetCurve {
+            distance_meters: 5_000.0,
+        }
+    }
+}
+
+impl OffsetCurve {
+    // The active line string in the working CRS, the distance in working CRS units and the
+    // offset curve, shared by the output and the snippet.
+    fn compute(
+        &self,
+        scene: &InputScene,
+    ) -> Result<(LineString<f64>, f64, LineString<f64>), String> {
+        let (geometry, units_per_meter) = active_with_scale(scene)?;
+        let Geometry::LineString(line) = geometry else {
+            return Err("Requires a line string input.".to_string());
+        };
+        let distance = self.distance_meters * units_per_meter;
+        let offset = line
+            .offset_curve(distance)
+            .ok_or_else(|| "The offset curve is undefined.".to_string())?;
+        Ok((line, distance, offset))
+    }
+}
+
+impl Algorithm for OffsetCurve {
+    fn name(&self) -> String {
+        "Offset Curve".to_string()
+    }
+
+    fn calculate_and_box_output(&mut self, scene: &InputScene) -> Option<AlgorithmOutput> {
+        let (_, _, offset) = match self.compute(scene) {
+            Ok(computed) => computed,
+            Err(message) => return Some(AlgorithmOutput::text(message)),
+        };
+
+        let text = format!("{} vertices", offset.0.len());
+        let overlays = from_working_crs(&offset, &scene.working_crs)
+            .map(|offset| vec![Overlay::line_string(offset, OFFSET_CURVE_COLOR)])
+            .unwrap_or_default();
+        Some(AlgorithmOutput::text(text).with_overlays(overlays))
+    }
+
+    fn has_doc_example(&self) -> bool {
+        true
+    }
+
+    fn doc_example(&self, scene: &InputScene) -> Result<String, String> {
+        let (line, distance, offset) = self.compute(scene)?;
+        Ok(DocExample::default()
+            .import("OffsetCurve")
+            .in_working_crs(&scene.working_crs)
+            .geometry("line", line)
+            .line(format!(
+                "let offset = line.offset_curve({:?}).unwrap();",
+                distance
+            ))
+            .assert_eq("offset.0.len()", offset.0.len())
+            .render())
+    }
+
+    fn settings_ui(&mut self, ui: &mut Ui) {
+        ui.add(
+            egui::Slider::new(&mut self.distance_meters, -50_000.0..=50_000.0).text("Offset (m)"),
+        );
+    }
+}
@@ -0,0 +1,287 @@
+//! Transforms that add vertices to the active input. Results are drawn with every vertex visible,
+//! so the inserted points stand out against the draggable originals.
+
+use super::{
+    Algorithm, AlgorithmOutput, DocExample, InputScene, active_in_working_crs, active_with_scale,
+    binding_name, from_working_crs, outline_overlays,
+};
+use crate::overlay::Overlay;
+use egui::{Color32, Ui};
+use geo::{
+    ChaikinSmoothing as _, CoordsIter, Densify as _, Euclidean, Geometry, Haversine, LineString,
+    LineStringSegmentize, LinesIter, MultiLineString,
+};
+
+const DENSIFY_COLOR: Color32 = Color32::from_rgb(0, 139, 139);
+const CHAIKIN_COLOR: Color32 = Color32::from_rgb(218, 112, 214);
+const SEGMENT_COLORS: [Color32; 2] = [
+    Color32::from_rgb(255, 69, 0),
+    Color32::from_rgb(30, 144, 255),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DensifyMetric {
+    /// Straight segments in the working CRS.
+    Euclidean,
+    /// Great circle segments in lon/lat.
+    Haversine,
+}
+
+/// Inserts vertices so that no segment is longer than the maximum.
+pub struct Densify {
+    metric: DensifyMetric,
+    pub max_segment_meters: f64,
+}
+
+impl Densify {
+    pub fn new(metric: DensifyMetric) -> Self {
+        Densify {
+            metric,
+            max_segment_meters: 10_000.0,
+        }
+    }
+
+    /// The input, the maximum segment length in its units and the densified input in lon/lat. The
+    /// input is in the working CRS for [`DensifyMetric::Euclidean`] and in lon/lat otherwise.
+    fn densified(&self, scene: &InputScene) -> Result<(Geometry<f64>, f64, Geometry<f64>), String> {
+        match self.metric {
+            DensifyMetric::Euclidean => {
+                let (geometry, units_per_meter) = active_with_scale(scene)?;
+                let max_segment_length = self.max_segment_meters * units_per_meter;
+                let densified = match &geometry {
+                    Geometry::LineString(line) => {
+                        line.densify(&Euclidean, max_segment_length).into()
+                    }
+                    Geometry::Polygon(polygon) => {
+                        polygon.densify(&Euclidean, max_segment_length).into()
+                    }
+                    _ => return Err("Requires a line string or polygon input.".to_string()),
+                };
+                let densified = from_working_crs(&densified, &scene.working_crs)
+                    .ok_or_else(|| format!("The result is outside {}.", scene.working_crs))?;
+                Ok((geometry, max_segment_length, densified))
+            }
+            DensifyMetric::Haversine => {
+                let geometry = &scene
+                    .active_geometry()
+                    .ok_or_else(|| "No input geometry is selected.".to_string())?
+                    .geometry;
+                let densified = match geometry {
+                    Geometry::LineString(line) => {
+                        line.densify(&Haversine, self.max_segment_meters).into()
+                    }
+                    Geometry::Polygon(polygon) => {
+                        polygon.densify(&Haversine, self.max_segment_meters).into()
+                    }
+                    _ => return Err("Requires a line string or polygon input.".to_string()),
+                };
+                Ok((geometry.clone(), self.max_segment_meters, densified))
+            }
+        }
+    }
+}
+
+impl Algorithm for Densify {
+    fn name(&self) -> String {
+        match self.metric {
+            DensifyMetric::Euclidean => "Densify (Euclidean)",
+            DensifyMetric::Haversine => "Densify (Haversine)",
+        }
+        .to_string()
+    }
+
+    fn calculate_and_box_output(&mut self, scene: &InputScene) -> Option<AlgorithmOutput> {
+        let (geometry, _, densified) = match self.densified(scene) {
+            Ok(result) => result,
+            Err(message) => return Some(AlgorithmOutput::text(message)),
+        };
+        let text = format!(
+            "{} -> {} vertices",
+            geometry.coords_count(),
+            densified.coords_count()
+        );
+        Some(AlgorithmOutput::text(text).with_overlays(outline_overlays(&densified, DENSIFY_COLOR)))
+    }
+
+    fn has_doc_example(&self) -> bool {
+        true
+    }
+
+    fn doc_example(&self, scene: &InputScene) -> Result<String, String> {
+        let (geometry, max_segment_length, densified) = self.densified(scene)?;
+        let example = DocExample::default().import("CoordsIter").import("Densify");
+        let (example, metric) = match self.metric {
+            DensifyMetric::Euclidean => (example.in_working_crs(&scene.working_crs), "Euclidean"),
+            DensifyMetric::Haversine => (example, "Haversine"),
+        };
+        let name = binding_name(&geometry);
+        Ok(example
+            .import(metric)
+            .geometry(name, geometry.clone())
+            .line(format!(
+                "let densified = {}.densify(&{}, {:?});",
+                name, metric, max_segment_length
+            ))
+            .assert_eq("densified.coords_count()", densified.coords_count())
+            .render())
+    }
+
+    fn settings_ui(&mut self, ui: &mut Ui) {
+        ui.add(
+            egui::Slider::new(&mut self.max_segment_meters, 100.0..=100_000.0)
+                .logarithmic(true)
+                .text("Max segment (m)"),
+        );
+    }
+}
+
+/// Corner cutting: every iteration replaces each vertex with two points a quarter of the way
+/// along its adjacent segments.
+pub struct ChaikinSmoothing {
+    pub iterations: usize,
+}
+
+impl Default for ChaikinSmoothing {
+    fn default() -> Self {
+        ChaikinSmoothing { iterations: 2 }
+    }
+}
+
+impl ChaikinSmoothing {
+    // The active input in the working CRS and its smoothed copy, shared by the output and the
+    // snippet.
+    fn smoothed(&self, scene: &InputScene) -> Result<(Geometry<f64>, Geometry<f64>), String> {
+        let geometry = active_in_working_crs(scene)?;
+        let smoothed: Geometry<f64> = match &geometry {
+            Geometry::LineString(line) => line.chaikin_smoothing(self.iterations).into(),
+            Geometry::Polygon(polygon) => polygon.chaikin_smoothing(self.iterations).into(),
+            _ => return Err("Requires a line string or polygon input.".to_string()),
+        };
+        Ok((geometry, smoothed))
+    }
+}
+
+impl Algorithm for ChaikinSmoothing {
+    fn name(&self) -> String {
+        "Chaikin Smoothing".to_string()
+    }
+
+    fn calculate_and_box_output(&mut self, scene: &InputScene) -> Option<AlgorithmOutput> {
+        let (geometry, smoothed) = match self.smoothed(scene) {
+            Ok(result) => result,
+            Err(message) => return Some(AlgorithmOutput::text(message)),
+        };
+        let text = format!(
+            "{} -> {} vertices",
+            geometry.coords_count(),
+            smoothed.coords_count()
+        );
+        let overlays = from_working_crs(&smoothed, &scene.working_crs)
+            .map(|smoothed| outline_overlays(&smoothed, CHAIKIN_COLOR))
+            .unwrap_or_default();
+        Some(AlgorithmOutput::text(text).with_overlays(overlays))
+    }
+
+    fn has_doc_example(&self) -> bool {
+        true
+    }
+
+    fn doc_example(&self, scene: &InputScene) -> Result<String, String> {
+        let (geometry, smoothed) = self.smoothed(scene)?;
+        let name = binding_name(&geometry);
+        Ok(DocExample::default()
+            .import("ChaikinSmoothing")
+            .import("CoordsIter")
+            .in_working_crs(&scene.working_crs)
+            .geometry(name, geometry.clone())
+            .line(format!(
+                "let smoothed = {}.chaikin_smoothing({});",
+                name, self.iterations
+            ))
+            .assert_eq("smoothed.coords_count()", smoothed.coords_count())
+            .render())
+    }
+
+    fn settings_ui(&mut self, ui: &mut Ui) {
+        ui.add(egui::Slider::new(&mut self.iterations, 0..=6).text("Iterations"));
+    }
+}
+
+/// Splits a line string into pieces of equal length, drawn in alternating colors.
+pub struct Segmentize {
+    pub pieces: usize,
+}
+
+impl Default for Segmentize {
+    fn default() -> Self {
+        Segmentize { pieces: 4 }
+    }
+}
+
+impl Segmentize {
+    // The active line string in the working CRS and its pieces, shared by the output and the
+    // snippet.
+    fn segmentized(
+        &self,
+        scene: &InputScene,
+    ) -> Result<(LineString<f64>, MultiLineString<f64>), String> {
+        let Geometry::LineString(line) = active_in_working_crs(scene)? else {
+            return Err("Requires a line string input.".to_string());
+        };
+        let pieces = line
+            .line_segmentize(self.pieces)
+            .ok_or_else(|| "Undefined for this line.".to_string())?;
+        Ok((line, pieces))
+    }
+}
+
+impl Algorithm for Segmentize {
+    fn name(&self) -> String {
+        "Segmentize".to_string()
+    }
+
+    fn calculate_and_box_output(&mut self, scene: &InputScene) -> Option<AlgorithmOutput> {
+        let (line, pieces) = match self.segmentized(scene) {
+            Ok(result) => result,
+            Err(message) => return Some(AlgorithmOutput::text(message)),
+        };
+
+        let text = format!(
+            "{} segments -> {} pieces",
+            line.lines_iter().count(),
+            pieces.0.len()
+        );
+        let overlays = from_working_crs(&pieces, &scene.working_crs)
+            .map(|pieces| {
+                pieces
+                    .into_iter()
+                    .zip(SEGMENT_COLORS.iter().cycle())
+                    .map(|(piece, color)| Overlay::line_string(piece, *color))
+                    .collect()
+            })
+            .unwrap_or_default();
+        Some(AlgorithmOutput::text(text).with_overlays(overlays))
+    }
+
+    fn has_doc_example(&self) -> bool {
+        true
+    }
+
+    fn doc_example(&self, scene: &InputScene) -> Result<String, String> {
+        let (line, pieces) = self.segmentized(scene)?;
+        Ok(DocExample::default()
+            .import("LineStringSegmentize")
+            .in_working_crs(&scene.working_crs)
+            .geometry("line", line)
+            .line(format!(
+                "let pieces = line.line_segmentize({}).unwrap();",
+                self.pieces
+            ))
+            .assert_eq("pieces.0.len()", pieces.0.len())
+            .render())
+    }
+
+    fn settings_ui(&mut self, ui: &mut Ui) {
+        ui.add(egui::Slider::new(&mut self.pieces, 1..=20).text("Pieces"));
+    }
+}
@@ -0,0 +1,128 @@
+use super::{Algorithm, AlgorithmOutput, DocExample, InputScene};
+use geo::{Coord, Distance, Euclidean, Haversine as GeoHaversine, Point as GeoPoint};
+
+pub struct HaversineDistance;
+
+// Internal types specific to HaversineDistance, NOT associated types of the Algorithm trait.
+impl HaversineDistance {
+    // Helper using concrete types specific to HaversineDistance
+    fn create_specific_input_for_haversine(
+        &self,
+        scene: &InputScene,
+    ) -> Result<(Coord<f64>, Coord<f64>), String> {
+        let points_vec: Vec<Coord<f64>> = scene
+            .first_line_string()
+            .map_or_else(Vec::new, |line| line.0.clone());
+        if points_vec.len() >= 2 {
+            Ok((points_vec[0], points_vec[1]))
+        } else {
+            Err("Haversine Distance: Requires at least two points.".to_string())
+        }
+    }
+
+    // Helper using concrete types specific to HaversineDistance
+    fn run_specific_calculation_for_haversine(
+        &self,
+        input: &(Coord<f64>, Coord<f64>),
+    ) -> Option<String> {
+        let distance = haversine_distance(input);
+        Some(format!("{:.2} meters", distance))
+    }
+}
+
+impl Algorithm for HaversineDistance {
+    fn name(&self) -> String {
+        "Haversine Distance".to_string()
+    }
+
+    fn calculate_and_box_output(&mut self, scene: &InputScene) -> Option<AlgorithmOutput> {
+        match self.create_specific_input_for_haversine(scene) {
+            Ok(specific_input) => {
+                let concrete_output: Option<String> =
+                    self.run_specific_calculation_for_haversine(&specific_input);
+                concrete_output.map(AlgorithmOutput::text)
+            }
+            Err(err_msg_string) => Some(AlgorithmOutput::text(err_msg_string)),
+        }
+    }
+
+    fn has_doc_example(&self) -> bool {
+        true
+    }
+
+    fn doc_example(&self, scene: &InputScene) -> Result<String, String> {
+        let (p1, p2) = self.create_specific_input_for_haversine(scene)?;
+        Ok(DocExample::default()
+            .import("Distance")
+            .import("Haversine")
+            .geometry("a", GeoPoint(p1))
+            .geometry("b", GeoPoint(p2))
+            .line("let distance = Haversine.distance(a, b);")
+            .assert_close("distance", haversine_distance(&(p1, p2)))
+            .render())
+    }
+}
+
+// Shared by the output and the snippet.
+fn haversine_distance(&(p1, p2): &(Coord<f64>, Coord<f64>)) -> f64 {
+    GeoHaversine.distance(GeoPoint(p1), GeoPoint(p2))
+}
+
+/// Straight-line distance between the first two points of the line after projecting them into the
+/// working CRS. Comparing it with `HaversineDistance` shows the scale distortion of each projection.
+pub struct EuclideanDistance;
+
+impl EuclideanDistance {
+    // The first two points in the working CRS and the distance between them, shared by the output
+    // and the snippet.
+    fn compute(scene: &InputScene) -> Result<(Coord<f64>, Coord<f64>, f64), String> {
+        let working_crs = &scene.working_crs;
+        let [p1, p2, ..] = scene
+            .first_line_string()
+            .map_or(&[][..], |line| &line.0[..])
+        else {
+            return Err("Requires at least two points.".to_string());
+        };
+        let (Some(p1), Some(p2)) = (working_crs.forward(*p1), working_crs.forward(*p2)) else {
+            return Err(format!("Points are outside {}.", working_crs));
+        };
+        Ok((p1, p2, Euclidean.distance(GeoPoint(p1), GeoPoint(p2))))
+    }
+}
+
+impl Algorithm for EuclideanDistance {
+    fn name(&self) -> String {
+        "Euclidean Distance".to_string()
+    }
+
+    fn calculate_and_box_output(&mut self, scene: &InputScene) -> Option<AlgorithmOutput> {
+        let working_crs = &scene.working_crs;
+        let output = match Self::compute(scene) {
+            Ok((_, _, distance)) => format!(
+                "{:.2} {} in {}",
+                distance,
+                working_crs.unit_suffix(),
+                working_crs
+            ),
+            Err(message) => format!("Euclidean Distance: {}", message),
+        };
+        Some(AlgorithmOutput::text(output))
+    }
+
+    fn has_doc_example(&self) -> bool {
+        true
+    }
+
+    fn doc_example(&self, scene: &InputScene) -> Result<String, String> {
+        let (p1, p2, distance) = Self::compute(scene)?;
+        Ok(DocExample::default()
+            .import("Distance")
+            .import("Euclidean")
+            .in_working_crs(&scene.working_crs)
+            .geometry("a", GeoPoint(p1))
+            .geometry("b", GeoPoint(p2))
+            .line("let distance = Euclidean.distance(a, b);")
+            .assert_close("distance", distance)
+            .render())
+    }
+}
@@ -0,0 +1,156 @@
+//! Rust snippets that reproduce an algorithm's current result with `geo`. The inputs are written out
+//! with `geo`'s macros, followed by the call and an `assert` on what the map shows, so a snippet can
+//! be pasted into a rustdoc example or a regression test as is.
+
+use std::collections::BTreeSet;
+use std::fmt::Display;
+
+use crate::crs::WorkingCrs;
+use geo::{Coord, Geometry, LineString};
+
+/// Results are compared to about this many significant digits, relative to their size. They are
+/// written out as decimal literals, and areas or projected coordinates in the millions would leave
+/// an absolute tolerance only a few ulps wide, which a `geo` or libm update can exceed.
+const SIGNIFICANT_DIGITS: i32 = 9;
+
+#[derive(Default)]
+pub struct DocExample {
+    /// Paths under `geo`, e.g. `"ConvexHull"` or `"orient::Direction"`.
+    imports: BTreeSet<String>,
+    lines: Vec<String>,
+}
+
+impl DocExample {
+    pub fn import(mut self, item: &str) -> Self {
+        self.imports.insert(item.to_string());
+        self
+    }
+
+    pub fn line(mut self, line: impl Into<String>) -> Self {
+        self.lines.push(line.into());
+        self
+    }
+
+    /// Notes which CRS the coordinates are in. Planar algorithms run on projected coordinates, so
+    /// the snippet uses those rather than the lon/lat shown in the window.
+    pub fn in_working_crs(self, working_crs: &WorkingCrs) -> Self {
+        self.line(format!("// Coordinates in {}.", working_crs))
+    }
+
+    /// Binds `name` to a macro literal of `geometry`.
+    pub fn geometry(self, name: &str, geometry: impl Into<Geometry<f64>>) -> Self {
+        match geometry.into() {
+            Geometry::Point(point) => self.import("point").line(format!(
+                "let {} = point!{};",
+                name,
+                coord_literal(point.0)
+            )),
+            Geometry::LineString(line) => self.import("line_string").line(format!(
+                "let {} = line_string![\n{}];",
+                name,
+                coord_lines(&line.0, 1)
+            )),
+            Geometry::Polygon(polygon) if polygon.interiors().is_empty() => {
+                self.import("polygon").line(format!(
+                    "let {} = polygon![\n{}];",
+                    name,
+                    coord_lines(open_ring(polygon.exterior()), 1)
+                ))
+            }
+            Geometry::Polygon(polygon) => {
+                let interiors: String = polygon
+                    .interiors()
+                    .iter()
+                    .map(|ring| {
+                        format!("        [\n{}        ],\n", coord_lines(open_ring(ring), 3))
+                    })
+                    .collect();
+                self.import("polygon").line(format!(
+                    "let {} = polygon!(\n    exterior: [\n{}    ],\n    interiors: [\n{}    ],\n);",
+                    name,
+                    coord_lines(open_ring(polygon.exterior()), 2),
+                    interiors
+                ))
+            }
+            Geometry::MultiPoint(points) => {
+                let coords: Vec<Coord<f64>> = points.iter().map(|point| point.0).collect();
+                let tuples: String = coords
+                    .iter()
+                    .map(|coord| format!("    ({:?}, {:?}),\n", coord.x, coord.y))
+                    .collect();
+                self.import("MultiPoint").line(format!(
+                    "let {} = MultiPoint::from(vec![\n{}]);",
+                    name, tuples
+                ))
+            }
+            _ => self.line(format!("// {} has no geo macro.", name)),
+        }
+    }
+
+    pub fn assert_eq(self, expression: &str, expected: impl Display) -> Self {
+        self.line(format!("assert_eq!({}, {});", expression, expected))
+    }
+
+    /// Asserts that a float expression matches `expected` to [`SIGNIFICANT_DIGITS`] digits.
+    pub fn assert_close(self, expression: &str, expected: f64) -> Self {
+        self.line(format!(
+            "assert!(({} - {:?}).abs() < {});",
+            expression,
+            expected,
+            tolerance(expected)
+        ))
+    }
+
+    pub fn assert_point_close(self, expression: &str, expected: Coord<f64>) -> Self {
+        self.assert_close(&format!("{}.x()", expression), expected.x)
+            .assert_close(&format!("{}.y()", expression), expected.y)
+    }
+
+    pub fn render(&self) -> String {
+        let imports: Vec<&str> = self.imports.iter().map(String::as_str).collect();
+        format!(
+            "use geo::{{{}}};\n\n{}\n",
+            imports.join(", "),
+            self.lines.join("\n")
+        )
+    }
+}
+
+/// The variable name a snippet binds an input of this kind to.
+pub fn binding_name(geometry: &Geometry<f64>) -> &'static str {
+    match geometry {
+        Geometry::Point(_) => "point",
+        Geometry::MultiPoint(_) => "points",
+        Geometry::LineString(_) => "line",
+        Geometry::Polygon(_) => "polygon",
+        _ => "geometry",
+    }
+}
+
+/// A power of ten, written as a literal, that is [`SIGNIFICANT_DIGITS`] orders of magnitude below
+/// `expected`, or below 1 for values smaller than that.
+fn tolerance(expected: f64) -> String {
+    let magnitude = expected.abs().max(1.0).log10().ceil().min(300.0) as i32;
+    format!("1e{}", magnitude - SIGNIFICANT_DIGITS)
+}
+
+/// `(x: 1.0, y: 2.0)`, the coordinate syntax of `geo`'s macros.
+fn coord_literal(coord: Coord<f64>) -> String {
+    format!("(x: {:?}, y: {:?})", coord.x, coord.y)
+}
+
+fn coord_lines(coords: &[Coord<f64>], depth: usize) -> String {
+    let indent = "    ".repeat(depth);
+    coords
+        .iter()
+        .map(|coord| format!("{}{},\n", indent, coord_literal(*coord)))
+        .collect()
+}
+
+/// A ring without its closing coordinate, which `polygon!` adds back.
+fn open_ring(ring: &LineString<f64>) -> &[Coord<f64>] {
+    match ring.0.split_last() {
+        Some((last, rest)) if ring.0.first() == Some(last) => rest,
+        _ => &ring.0,
+    }
+}
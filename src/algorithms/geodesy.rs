@@ -0,0 +1,345 @@
+//! Bearings, destinations and midpoints on the sphere and the ellipsoid, between the first two
+//! points of the first line like `HaversineDistance`. Each result is computed with the Haversine,
+//! Geodesic and Rhumb metrics so their differences can be compared.
+
+use super::{Algorithm, AlgorithmOutput, DocExample, InputScene, OutputTable};
+use crate::overlay::Overlay;
+use egui::{Color32, Ui};
+use geo::{
+    Bearing as _, Coord, Destination as _, Geodesic, Haversine, InterpolatePoint as _, Point, Rhumb,
+};
+
+const COMPASS_COLOR: Color32 = Color32::from_rgb(220, 20, 60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GeoMetric {
+    Haversine,
+    Geodesic,
+    Rhumb,
+}
+
+const METRICS: [GeoMetric; 3] = [GeoMetric::Haversine, GeoMetric::Geodesic, GeoMetric::Rhumb];
+
+impl GeoMetric {
+    fn name(self) -> &'static str {
+        match self {
+            GeoMetric::Haversine => "Haversine",
+            GeoMetric::Geodesic => "Geodesic",
+            GeoMetric::Rhumb => "Rhumb",
+        }
+    }
+
+    fn color(self) -> Color32 {
+        match self {
+            GeoMetric::Haversine => Color32::from_rgb(0, 128, 255),
+            GeoMetric::Geodesic => Color32::from_rgb(0, 160, 80),
+            GeoMetric::Rhumb => Color32::from_rgb(255, 140, 0),
+        }
+    }
+
+    /// Degrees clockwise from north, in `0.0..360.0`.
+    fn bearing(self, from: Point<f64>, to: Point<f64>) -> f64 {
+        let bearing = match self {
+            GeoMetric::Haversine => Haversine.bearing(from, to),
+            GeoMetric::Geodesic => Geodesic.bearing(from, to),
+            GeoMetric::Rhumb => Rhumb.bearing(from, to),
+        };
+        bearing.rem_euclid(360.0)
+    }
+
+    /// The bearing of the path on arrival at `to`. A rhumb line keeps its bearing, while a great
+    /// circle arrives at the reverse of the bearing back to the start.
+    fn final_bearing(self, from: Point<f64>, to: Point<f64>) -> f64 {
+        match self {
+            GeoMetric::Rhumb => self.bearing(from, to),
+            _ => (self.bearing(to, from) + 180.0).rem_euclid(360.0),
+        }
+    }
+
+    fn destination(self, origin: Point<f64>, bearing: f64, distance: f64) -> Point<f64> {
+        match self {
+            GeoMetric::Haversine => Haversine.destination(origin, bearing, distance),
+            GeoMetric::Geodesic => Geodesic.destination(origin, bearing, distance),
+            GeoMetric::Rhumb => Rhumb.destination(origin, bearing, distance),
+        }
+    }
+
+    fn midpoint(self, start: Point<f64>, end: Point<f64>) -> Point<f64> {
+        match self {
+            GeoMetric::Haversine => Haversine.point_at_ratio_between(start, end, 0.5),
+            GeoMetric::Geodesic => Geodesic.point_at_ratio_between(start, end, 0.5),
+            GeoMetric::Rhumb => Rhumb.point_at_ratio_between(start, end, 0.5),
+        }
+    }
+}
+
+fn first_two_points(scene: &InputScene) -> Result<(Point<f64>, Point<f64>), String> {
+    let points: &[Coord<f64>] = scene.first_line_string().map_or(&[], |line| &line.0);
+    match points {
+        [start, end, ..] => Ok((Point(*start), Point(*end))),
+        _ => Err("Requires at least two points.".to_string()),
+    }
+}
+
+/// The geodesic entry of a per-metric result, which is the one the snippets reproduce.
+fn geodesic<T: Copy>(results: &[(GeoMetric, T)]) -> T {
+    results
+        .iter()
+        .find(|(metric, _)| *metric == GeoMetric::Geodesic)
+        .map(|(_, result)| *result)
+        .expect("every metric has a result")
+}
+
+fn lonlat_row(metric: GeoMetric, point: Point<f64>) -> Vec<String> {
+    vec![
+        metric.name().to_string(),
+        format!("{:.6}", point.x()),
+        format!("{:.6}", point.y()),
+    ]
+}
+
+/// Initial and final bearing from the first point to the second.
+pub struct Bearing;
+
+impl Bearing {
+    /// The two points and each metric's initial and final bearing, for the output and the
+    /// snippet alike.
+    fn compute(
+        scene: &InputScene,
+    ) -> Result<(Point<f64>, Point<f64>, Vec<(GeoMetric, (f64, f64))>), String> {
+        let (start, end) = first_two_points(scene)?;
+        let bearings = METRICS
+            .iter()
+            .map(|metric| {
+                (
+                    *metric,
+                    (metric.bearing(start, end), metric.final_bearing(start, end)),
+                )
+            })
+            .collect();
+        Ok((start, end, bearings))
+    }
+}
+
+impl Algorithm for Bearing {
+    fn name(&self) -> String {
+        "Bearing".to_string()
+    }
+
+    fn calculate_and_box_output(&mut self, scene: &InputScene) -> Option<AlgorithmOutput> {
+        let (start, end, bearings) = match Bearing::compute(scene) {
+            Ok(bearings) => bearings,
+            Err(message) => return Some(AlgorithmOutput::text(message)),
+        };
+        let (initial, _) = geodesic(&bearings);
+
+        let text = format!("{:.2}° initial (geodesic)", initial);
+        let table = OutputTable {
+            headers: ["Metric", "Initial", "Final"].map(String::from).to_vec(),
+            rows: bearings
+                .iter()
+                .map(|(metric, (initial, final_bearing))| {
+                    vec![
+                        metric.name().to_string(),
+                        format!("{:.2}°", initial),
+                        format!("{:.2}°", final_bearing),
+                    ]
+                })
+                .collect(),
+        };
+        let overlays = vec![
+            Overlay::compass(start.0, initial, COMPASS_COLOR),
+            Overlay::connector(start.0, end.0, COMPASS_COLOR),
+        ];
+        Some(
+            AlgorithmOutput::text(text)
+                .with_overlays(overlays)
+                .with_table(table),
+        )
+    }
+
+    fn has_doc_example(&self) -> bool {
+        true
+    }
+
+    fn doc_example(&self, scene: &InputScene) -> Result<String, String> {
+        let (start, end, bearings) = Bearing::compute(scene)?;
+        let (initial, _) = geodesic(&bearings);
+        Ok(DocExample::default()
+            .import("Bearing")
+            .import("Geodesic")
+            .geometry("start", start)
+            .geometry("end", end)
+            .line("let bearing = Geodesic.bearing(start, end).rem_euclid(360.0);")
+            .assert_close("bearing", initial)
+            .render())
+    }
+}
+
+/// The point reached from the first point by travelling a distance along a bearing.
+pub struct Destination {
+    pub bearing: f64,
+    pub distance_meters: f64,
+}
+
+impl Default for Destination {
+    fn default() -> Self {
+        Destination {
+            bearing: 45.0,
+            distance_meters: 50_000.0,
+        }
+    }
+}
+
+impl Destination {
+    /// The start point and where each metric ends up from it.
+    fn compute(
+        &self,
+        scene: &InputScene,
+    ) -> Result<(Point<f64>, Vec<(GeoMetric, Point<f64>)>), String> {
+        let (start, _) = first_two_points(scene)?;
+        let destinations = METRICS
+            .iter()
+            .map(|metric| {
+                (
+                    *metric,
+                    metric.destination(start, self.bearing, self.distance_meters),
+                )
+            })
+            .collect();
+        Ok((start, destinations))
+    }
+}
+
+impl Algorithm for Destination {
+    fn name(&self) -> String {
+        "Destination".to_string()
+    }
+
+    fn calculate_and_box_output(&mut self, scene: &InputScene) -> Option<AlgorithmOutput> {
+        let (start, destinations) = match self.compute(scene) {
+            Ok(destinations) => destinations,
+            Err(message) => return Some(AlgorithmOutput::text(message)),
+        };
+
+        let text = format!(
+            "{:.0} m at {:.1}° from {:.6}, {:.6}",
+            self.distance_meters,
+            self.bearing,
+            start.x(),
+            start.y()
+        );
+        let table = OutputTable {
+            headers: ["Metric", "Longitude", "Latitude"]
+                .map(String::from)
+                .to_vec(),
+            rows: destinations
+                .iter()
+                .map(|(metric, point)| lonlat_row(*metric, *point))
+                .collect(),
+        };
+        let overlays = std::iter::once(Overlay::compass(start.0, self.bearing, COMPASS_COLOR))
+            .chain(destinations.iter().map(|(metric, point)| {
+                Overlay::labeled_marker(point.0, metric.name(), metric.color())
+            }))
+            .collect();
+        Some(
+            AlgorithmOutput::text(text)
+                .with_overlays(overlays)
+                .with_table(table),
+        )
+    }
+
+    fn has_doc_example(&self) -> bool {
+        true
+    }
+
+    fn doc_example(&self, scene: &InputScene) -> Result<String, String> {
+        let (start, destinations) = self.compute(scene)?;
+        let destination = geodesic(&destinations);
+        Ok(DocExample::default()
+            .import("Destination")
+            .import("Geodesic")
+            .geometry("start", start)
+            .line(format!(
+                "let destination = Geodesic.destination(start, {:?}, {:?});",
+                self.bearing, self.distance_meters
+            ))
+            .assert_point_close("destination", destination.0)
+            .render())
+    }
+
+    fn settings_ui(&mut self, ui: &mut Ui) {
+        ui.add(egui::Slider::new(&mut self.bearing, 0.0..=360.0).text("Bearing (°)"));
+        ui.add(
+            egui::Slider::new(&mut self.distance_meters, 0.0..=1_000_000.0)
+                .logarithmic(true)
+                .text("Distance (m)"),
+        );
+    }
+}
+
+/// The point halfway between the first two points.
+pub struct Midpoint;
+
+impl Midpoint {
+    /// The two points and each metric's midpoint between them.
+    fn compute(
+        scene: &InputScene,
+    ) -> Result<(Point<f64>, Point<f64>, Vec<(GeoMetric, Point<f64>)>), String> {
+        let (start, end) = first_two_points(scene)?;
+        let midpoints = METRICS
+            .iter()
+            .map(|metric| (*metric, metric.midpoint(start, end)))
+            .collect();
+        Ok((start, end, midpoints))
+    }
+}
+
+impl Algorithm for Midpoint {
+    fn name(&self) -> String {
+        "Midpoint".to_string()
+    }
+
+    fn calculate_and_box_output(&mut self, scene: &InputScene) -> Option<AlgorithmOutput> {
+        let (_, _, midpoints) = match Midpoint::compute(scene) {
+            Ok(midpoints) => midpoints,
+            Err(message) => return Some(AlgorithmOutput::text(message)),
+        };
+
+        let table = OutputTable {
+            headers: ["Metric", "Longitude", "Latitude"]
+                .map(String::from)
+                .to_vec(),
+            rows: midpoints
+                .iter()
+                .map(|(metric, point)| lonlat_row(*metric, *point))
+                .collect(),
+        };
+        let overlays = midpoints
+            .iter()
+            .map(|(metric, point)| Overlay::labeled_marker(point.0, metric.name(), metric.color()))
+            .collect();
+        Some(
+            AlgorithmOutput::text(format!("{} variants", midpoints.len()))
+                .with_overlays(overlays)
+                .with_table(table),
+        )
+    }
+
+    fn has_doc_example(&self) -> bool {
+        true
+    }
+
+    fn doc_example(&self, scene: &InputScene) -> Result<String, String> {
+        let (start, end, midpoints) = Midpoint::compute(scene)?;
+        Ok(DocExample::default()
+            .import("Geodesic")
+            .import("InterpolatePoint")
+            .geometry("start", start)
+            .geometry("end", end)
+            .line("let midpoint = Geodesic.point_at_ratio_between(start, end, 0.5);")
+            .assert_point_close("midpoint", geodesic(&midpoints).0)
+            .render())
+    }
+}
@@ -0,0 +1,262 @@
+//! Hulls around all the draggable points. Dragging a point across the hull boundary shows which
+//! points end up on it.
+//!
+//! The convex hull can be stepped through. `geo` computes it with QuickHull, which the trace reruns
+//! on the points, one hull edge at a time.
+
+use super::{
+    Algorithm, AlgorithmOutput, DocExample, InputScene, TraceStep, from_working_crs, to_working_crs,
+};
+use crate::crs::WorkingCrs;
+use crate::overlay::Overlay;
+use egui::{Color32, Ui};
+use geo::{
+    Area, ConcaveHull as _, ConvexHull as _, Coord, CoordsIter, LineString, MultiPoint, Polygon,
+};
+
+const CONVEX_HULL_COLOR: Color32 = Color32::from_rgb(30, 144, 255);
+const CONCAVE_HULL_COLOR: Color32 = Color32::from_rgb(255, 99, 71);
+const POINT_COLOR: Color32 = Color32::from_rgb(128, 128, 128);
+const OUTSIDE_COLOR: Color32 = Color32::from_rgb(255, 140, 0);
+
+pub struct ConvexHull;
+
+impl Algorithm for ConvexHull {
+    fn name(&self) -> String {
+        "Convex Hull".to_string()
+    }
+
+    fn calculate_and_box_output(&mut self, scene: &InputScene) -> Option<AlgorithmOutput> {
+        let hull = match hull_of(scene, |points| points.convex_hull()) {
+            Ok((_, hull)) => hull,
+            Err(message) => return Some(AlgorithmOutput::text(message)),
+        };
+        Some(hull_output(&hull, &scene.working_crs, CONVEX_HULL_COLOR))
+    }
+
+    fn has_trace(&self) -> bool {
+        true
+    }
+
+    fn trace(&mut self, scene: &InputScene) -> Result<Vec<TraceStep>, String> {
+        let projected = points_in_working_crs(scene)?;
+        let projected: Vec<Coord<f64>> = projected.0.iter().map(|point| point.0).collect();
+        let lonlat: Vec<Coord<f64>> = scene.all_vertices().0.iter().map(|point| point.0).collect();
+        Ok(quickhull_trace(&lonlat, &projected))
+    }
+
+    fn has_doc_example(&self) -> bool {
+        true
+    }
+
+    fn doc_example(&self, scene: &InputScene) -> Result<String, String> {
+        let (points, hull) = hull_of(scene, |points| points.convex_hull())?;
+        Ok(hull_example(scene, points, "ConvexHull")
+            .line("let hull = points.convex_hull();")
+            .assert_eq("hull.exterior().0.len()", hull.exterior().0.len())
+            .render())
+    }
+}
+
+/// QuickHull on the projected points. The hull starts as the edge between the leftmost and the
+/// rightmost point, run both ways. Each step takes a hull edge, adds the point furthest outside
+/// it, and discards the points that end up inside the new triangle.
+fn quickhull_trace(lonlat: &[Coord<f64>], projected: &[Coord<f64>]) -> Vec<TraceStep> {
+    let by_xy = |a: &usize, b: &usize| {
+        let (a, b) = (projected[*a], projected[*b]);
+        a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y))
+    };
+    let (Some(left), Some(right)) = (
+        (0..projected.len()).min_by(by_xy),
+        (0..projected.len()).max_by(by_xy),
+    ) else {
+        return Vec::new();
+    };
+    // How far `p` is to the right of the edge from `a` to `b`, times the edge's length.
+    let outside = |a: usize, b: usize, p: usize| {
+        let (a, b, p) = (projected[a], projected[b], projected[p]);
+        (b.y - a.y) * (p.x - a.x) - (b.x - a.x) * (p.y - a.y)
+    };
+    let outside_of = |a: usize, b: usize, points: &[usize]| -> Vec<usize> {
+        points
+            .iter()
+            .copied()
+            .filter(|p| outside(a, b, *p) > 0.0)
+            .collect()
+    };
+
+    let all: Vec<usize> = (0..projected.len()).collect();
+    // Hull vertices in counter-clockwise order.
+    let mut hull = vec![left, right];
+    let step = |description: String, hull: &[usize], a: usize, b: usize, candidates: &[usize]| {
+        let ring: LineString<f64> = hull
+            .iter()
+            .chain(hull.first())
+            .map(|i| lonlat[*i])
+            .collect();
+        TraceStep::new(
+            description,
+            vec![
+                Overlay::point_cloud(lonlat.to_vec(), POINT_COLOR),
+                Overlay::line_string(ring, CONVEX_HULL_COLOR),
+                Overlay::connector(lonlat[a], lonlat[b], OUTSIDE_COLOR),
+                Overlay::point_cloud(
+                    candidates.iter().map(|i| lonlat[*i]).collect(),
+                    OUTSIDE_COLOR,
+                ),
+            ],
+        )
+    };
+    let mut steps = vec![step(
+        format!(
+            "Start with the leftmost point {} and the rightmost point {}",
+            left, right
+        ),
+        &hull,
+        left,
+        right,
+        &[],
+    )];
+
+    let mut pending = vec![
+        (right, left, outside_of(right, left, &all)),
+        (left, right, outside_of(left, right, &all)),
+    ];
+    while let Some((a, b, candidates)) = pending.pop() {
+        let Some(farthest) = candidates
+            .iter()
+            .copied()
+            .max_by(|p, q| outside(a, b, *p).total_cmp(&outside(a, b, *q)))
+        else {
+            steps.push(step(
+                format!("Edge {}-{}: no points outside, it is a hull edge", a, b),
+                &hull,
+                a,
+                b,
+                &[],
+            ));
+            continue;
+        };
+        let before_a = outside_of(a, farthest, &candidates);
+        let after_b = outside_of(farthest, b, &candidates);
+        // Near-collinear points can count as outside both new edges, so this must not underflow.
+        let discarded = (candidates.len() - 1).saturating_sub(before_a.len() + after_b.len());
+        let mut description = format!(
+            "Edge {}-{}: {} points outside, point {} is furthest; add it",
+            a,
+            b,
+            candidates.len(),
+            farthest
+        );
+        if discarded > 0 {
+            description += &format!(" and discard {} inside the new triangle", discarded);
+        }
+        steps.push(step(description, &hull, a, b, &candidates));
+
+        let position = hull
+            .iter()
+            .position(|i| *i == a)
+            .map_or(hull.len(), |i| i + 1);
+        hull.insert(position, farthest);
+        // Pushed in reverse, so the edges are traced in hull order.
+        pending.push((farthest, b, after_b));
+        pending.push((a, farthest, before_a));
+    }
+
+    steps.push(TraceStep::new(
+        format!("Result: {} hull vertices", hull.len()),
+        vec![
+            Overlay::point_cloud(lonlat.to_vec(), POINT_COLOR),
+            Overlay::polygon(
+                Polygon::new(hull.iter().map(|i| lonlat[*i]).collect(), Vec::new()),
+                CONVEX_HULL_COLOR,
+            ),
+        ],
+    ));
+    steps
+}
+
+pub struct ConcaveHull {
+    /// Lower values follow the points more tightly, higher values approach the convex hull.
+    pub concavity: f64,
+}
+
+impl Default for ConcaveHull {
+    fn default() -> Self {
+        ConcaveHull { concavity: 2.0 }
+    }
+}
+
+impl Algorithm for ConcaveHull {
+    fn name(&self) -> String {
+        "Concave Hull".to_string()
+    }
+
+    fn calculate_and_box_output(&mut self, scene: &InputScene) -> Option<AlgorithmOutput> {
+        let hull = match hull_of(scene, |points| points.concave_hull(self.concavity)) {
+            Ok((_, hull)) => hull,
+            Err(message) => return Some(AlgorithmOutput::text(message)),
+        };
+        Some(hull_output(&hull, &scene.working_crs, CONCAVE_HULL_COLOR))
+    }
+
+    fn settings_ui(&mut self, ui: &mut Ui) {
+        ui.add(egui::Slider::new(&mut self.concavity, 0.0..=10.0).text("Concavity"));
+    }
+
+    fn has_doc_example(&self) -> bool {
+        true
+    }
+
+    fn doc_example(&self, scene: &InputScene) -> Result<String, String> {
+        let (points, hull) = hull_of(scene, |points| points.concave_hull(self.concavity))?;
+        Ok(hull_example(scene, points, "ConcaveHull")
+            .line(format!(
+                "let hull = points.concave_hull({:?});",
+                self.concavity
+            ))
+            .assert_eq("hull.exterior().0.len()", hull.exterior().0.len())
+            .render())
+    }
+}
+
+// A snippet importing `algorithm` and binding all the draggable points in the working CRS.
+fn hull_example(scene: &InputScene, points: MultiPoint<f64>, algorithm: &str) -> DocExample {
+    DocExample::default()
+        .import(algorithm)
+        .in_working_crs(&scene.working_crs)
+        .geometry("points", points)
+}
+
+fn points_in_working_crs(scene: &InputScene) -> Result<MultiPoint<f64>, String> {
+    let points = scene.all_vertices();
+    if points.0.len() < 3 {
+        return Err("Requires at least three points.".to_string());
+    }
+    to_working_crs(&points, &scene.working_crs)
+        .ok_or_else(|| format!("Points are outside {}.", scene.working_crs))
+}
+
+// The points in the working CRS and their hull, shared by the output and the snippet.
+fn hull_of(
+    scene: &InputScene,
+    hull: impl Fn(&MultiPoint<f64>) -> Polygon<f64>,
+) -> Result<(MultiPoint<f64>, Polygon<f64>), String> {
+    let points = points_in_working_crs(scene)?;
+    let hull = hull(&points);
+    Ok((points, hull))
+}
+
+fn hull_output(hull: &Polygon<f64>, working_crs: &WorkingCrs, color: Color32) -> AlgorithmOutput {
+    let text = format!(
+        "{} vertices, area {:.2} {}²",
+        // The exterior ring repeats its first vertex at the end.
+        hull.exterior().coords_count().saturating_sub(1),
+        hull.unsigned_area(),
+        working_crs.unit_suffix()
+    );
+    let overlays = from_working_crs(hull, working_crs)
+        .map(|hull| vec![Overlay::polygon(hull, color)])
+        .unwrap_or_default();
+    AlgorithmOutput::text(text).with_overlays(overlays)
+}
@@ -0,0 +1,270 @@
+//! Points on the active input found from a probe point or a fraction. The probe is the first
+//! vertex of the second geometry, so it can be dragged around the map.
+
+use super::{
+    Algorithm, AlgorithmOutput, DocExample, InputScene, active_in_working_crs, binding_name,
+};
+use crate::crs::WorkingCrs;
+use crate::overlay::Overlay;
+use egui::{Color32, Ui};
+use geo::{
+    Closest, ClosestPoint as _, Coord, CoordsIter, Distance, Euclidean, Geometry,
+    LineInterpolatePoint as _, LineLocatePoint as _, LineString, Point,
+};
+
+const CLOSEST_POINT_COLOR: Color32 = Color32::from_rgb(255, 20, 147);
+const LINE_LOCATE_COLOR: Color32 = Color32::from_rgb(0, 191, 255);
+const LINE_INTERPOLATE_COLOR: Color32 = Color32::from_rgb(255, 140, 0);
+
+pub struct ClosestPoint;
+
+impl ClosestPoint {
+    // The active input, the probe, whether the probe lies on the input and the closest point, all
+    // in the working CRS. Shared by the output and the snippet.
+    fn compute(
+        scene: &InputScene,
+    ) -> Result<(Geometry<f64>, Point<f64>, bool, Point<f64>), String> {
+        let (geometry, probe) = active_and_probe(scene)?;
+        let (intersects, closest) = match geometry.closest_point(&probe) {
+            Closest::Intersection(point) => (true, point),
+            Closest::SinglePoint(point) => (false, point),
+            Closest::Indeterminate => {
+                return Err("Indeterminate, e.g. for an empty geometry.".to_string());
+            }
+        };
+        Ok((geometry, probe, intersects, closest))
+    }
+}
+
+impl Algorithm for ClosestPoint {
+    fn name(&self) -> String {
+        "Closest Point".to_string()
+    }
+
+    fn calculate_and_box_output(&mut self, scene: &InputScene) -> Option<AlgorithmOutput> {
+        let (_, probe, intersects, closest) = match Self::compute(scene) {
+            Ok(result) => result,
+            Err(message) => return Some(AlgorithmOutput::text(message)),
+        };
+        let text = format!(
+            "{}, {:.2} {} from the probe",
+            if intersects {
+                "intersection"
+            } else {
+                "single point"
+            },
+            Euclidean.distance(probe, closest),
+            scene.working_crs.unit_suffix()
+        );
+        Some(probe_result_output(
+            text,
+            probe,
+            closest,
+            &scene.working_crs,
+            CLOSEST_POINT_COLOR,
+        ))
+    }
+
+    fn has_doc_example(&self) -> bool {
+        true
+    }
+
+    fn doc_example(&self, scene: &InputScene) -> Result<String, String> {
+        let (geometry, probe, intersects, closest) = Self::compute(scene)?;
+        let variant = if intersects {
+            "Intersection"
+        } else {
+            "SinglePoint"
+        };
+        let name = binding_name(&geometry);
+        Ok(probe_example(scene, &geometry, probe)
+            .import("Closest")
+            .import("ClosestPoint")
+            .line(format!(
+                "let Closest::{}(closest) = {}.closest_point(&probe) else {{\n    panic!(\"expected Closest::{}\");\n}};",
+                variant, name, variant
+            ))
+            .assert_point_close("closest", closest.0)
+            .render())
+    }
+}
+
+/// Fraction of the line's length at which the point closest to the probe lies.
+pub struct LineLocatePoint;
+
+impl LineLocatePoint {
+    // The active input, the probe, the fraction and the point at that fraction, shared by the
+    // output and the snippet.
+    fn compute(scene: &InputScene) -> Result<(Geometry<f64>, Point<f64>, f64, Point<f64>), String> {
+        let (geometry, probe) = active_and_probe(scene)?;
+        let line =
+            as_line_string(&geometry).ok_or_else(|| "Requires a line string input.".to_string())?;
+        let (fraction, located) = line
+            .line_locate_point(&probe)
+            .and_then(|fraction| Some((fraction, line.line_interpolate_point(fraction)?)))
+            .ok_or_else(|| "Undefined for this line.".to_string())?;
+        Ok((geometry, probe, fraction, located))
+    }
+}
+
+impl Algorithm for LineLocatePoint {
+    fn name(&self) -> String {
+        "Line Locate Point".to_string()
+    }
+
+    fn calculate_and_box_output(&mut self, scene: &InputScene) -> Option<AlgorithmOutput> {
+        let (_, probe, fraction, located) = match Self::compute(scene) {
+            Ok(result) => result,
+            Err(message) => return Some(AlgorithmOutput::text(message)),
+        };
+        Some(probe_result_output(
+            format!("fraction {:.4}", fraction),
+            probe,
+            located,
+            &scene.working_crs,
+            LINE_LOCATE_COLOR,
+        ))
+    }
+
+    fn has_doc_example(&self) -> bool {
+        true
+    }
+
+    fn doc_example(&self, scene: &InputScene) -> Result<String, String> {
+        let (geometry, probe, fraction, _) = Self::compute(scene)?;
+        Ok(probe_example(scene, &geometry, probe)
+            .import("LineLocatePoint")
+            .line("let fraction = line.line_locate_point(&probe).unwrap();")
+            .assert_close("fraction", fraction)
+            .render())
+    }
+}
+
+/// The point at a given fraction of the line's length.
+pub struct LineInterpolatePoint {
+    pub fraction: f64,
+}
+
+impl Default for LineInterpolatePoint {
+    fn default() -> Self {
+        LineInterpolatePoint { fraction: 0.5 }
+    }
+}
+
+impl LineInterpolatePoint {
+    // The active line string and the interpolated point in the working CRS, shared by the output
+    // and the snippet.
+    fn compute(&self, scene: &InputScene) -> Result<(LineString<f64>, Point<f64>), String> {
+        let geometry = active_in_working_crs(scene)?;
+        let line =
+            as_line_string(&geometry).ok_or_else(|| "Requires a line string input.".to_string())?;
+        let point = line
+            .line_interpolate_point(self.fraction)
+            .ok_or_else(|| "Undefined for this line.".to_string())?;
+        Ok((line.clone(), point))
+    }
+}
+
+impl Algorithm for LineInterpolatePoint {
+    fn name(&self) -> String {
+        "Line Interpolate Point".to_string()
+    }
+
+    fn calculate_and_box_output(&mut self, scene: &InputScene) -> Option<AlgorithmOutput> {
+        let (line, point) = match self.compute(scene) {
+            Ok(result) => result,
+            Err(message) => return Some(AlgorithmOutput::text(message)),
+        };
+        // An interpolated point exists only for a line with vertices.
+        let start = line.0[0];
+        let (Some(start), Some(position)) = (
+            scene.working_crs.inverse(start),
+            scene.working_crs.inverse(point.0),
+        ) else {
+            return Some(AlgorithmOutput::text(format!(
+                "The point is outside {}.",
+                scene.working_crs
+            )));
+        };
+
+        let text = format!("{:.6}, {:.6}", position.x, position.y);
+        Some(AlgorithmOutput::text(text).with_overlays(vec![
+            Overlay::connector(start, position, LINE_INTERPOLATE_COLOR),
+            Overlay::labeled_marker(
+                position,
+                format!("{:.2}", self.fraction),
+                LINE_INTERPOLATE_COLOR,
+            ),
+        ]))
+    }
+
+    fn has_doc_example(&self) -> bool {
+        true
+    }
+
+    fn doc_example(&self, scene: &InputScene) -> Result<String, String> {
+        let (line, point) = self.compute(scene)?;
+        Ok(DocExample::default()
+            .import("LineInterpolatePoint")
+            .in_working_crs(&scene.working_crs)
+            .geometry("line", line.clone())
+            .line(format!(
+                "let point = line.line_interpolate_point({:?}).unwrap();",
+                self.fraction
+            ))
+            .assert_point_close("point", point.0)
+            .render())
+    }
+
+    fn settings_ui(&mut self, ui: &mut Ui) {
+        ui.add(egui::Slider::new(&mut self.fraction, 0.0..=1.0).text("Fraction"));
+    }
+}
+
+// The active input and the probe point, both in the working CRS.
+fn active_and_probe(scene: &InputScene) -> Result<(Geometry<f64>, Point<f64>), String> {
+    let geometry = active_in_working_crs(scene)?;
+    let probe = scene
+        .secondary_geometry()
+        .and_then(|input| input.geometry.coords_iter().next())
+        .ok_or_else(|| "The second geometry has no probe point.".to_string())?;
+    let probe = scene
+        .working_crs
+        .forward(probe)
+        .ok_or_else(|| format!("The probe is outside {}.", scene.working_crs))?;
+    Ok((geometry, Point(probe)))
+}
+
+// A snippet binding the active input and the probe, both in the working CRS.
+fn probe_example(scene: &InputScene, geometry: &Geometry<f64>, probe: Point<f64>) -> DocExample {
+    DocExample::default()
+        .in_working_crs(&scene.working_crs)
+        .geometry(binding_name(geometry), geometry.clone())
+        .geometry("probe", probe)
+}
+
+fn as_line_string(geometry: &Geometry<f64>) -> Option<&LineString<f64>> {
+    match geometry {
+        Geometry::LineString(line) => Some(line),
+        _ => None,
+    }
+}
+
+// A marker at `result` with a connector from the probe, both given in the working CRS.
+fn probe_result_output(
+    text: String,
+    probe: Point<f64>,
+    result: Point<f64>,
+    working_crs: &WorkingCrs,
+    color: Color32,
+) -> AlgorithmOutput {
+    let to_lonlat = |point: Point<f64>| -> Option<Coord<f64>> { working_crs.inverse(point.0) };
+    let overlays = match (to_lonlat(probe), to_lonlat(result)) {
+        (Some(probe), Some(result)) => vec![
+            Overlay::connector(probe, result, color),
+            Overlay::marker(result, color),
+        ],
+        _ => Vec::new(),
+    };
+    AlgorithmOutput::text(text).with_overlays(overlays)
+}
@@ -0,0 +1,318 @@
+use crate::crs::WorkingCrs;
+use crate::overlay::Overlay;
+use egui::{Color32, Ui};
+use geo::{
+    Centroid as _, CoordsIter, Geometry, LineString, MapCoords, MultiPoint, MultiPolygon, Point,
+    Polygon,
+};
+use std::fmt::Display;
+
+mod affine;
+mod area;
+mod boolean;
+mod buffer;
+mod densify;
+mod distance;
+mod doc_example;
+mod geodesy;
+mod hull;
+mod locate;
+mod orient;
+mod predicates;
+mod representative;
+mod similarity;
+mod simplify;
+mod spatial_index;
+mod triangulate;
+mod validation;
+
+pub use affine::{AffineKind, AffineParams, AffineTransformAlgorithm, TransformOrigin};
+pub use area::AreaComparison;
+pub use boolean::{BooleanOp, BooleanOperation};
+pub use buffer::{Buffer, CapStyle, JoinStyle, OffsetCurve};
+pub use densify::{ChaikinSmoothing, Densify, DensifyMetric, Segmentize};
+pub use distance::{EuclideanDistance, HaversineDistance};
+pub use doc_example::{DocExample, binding_name};
+pub use geodesy::{Bearing, Destination, Midpoint};
+pub use hull::{ConcaveHull, ConvexHull};
+pub use locate::{ClosestPoint, LineInterpolatePoint, LineLocatePoint};
+pub use orient::{Orient, Winding};
+pub use predicates::PredicateMatrix;
+pub use representative::{BoundingRect, Centroid, Extremes, InteriorPoint, MinimumRotatedRect};
+pub use similarity::{FrechetDistance, HausdorffDistance};
+pub use simplify::{SimplifyLine, SimplifyMethod};
+pub use spatial_index::SpatialIndex;
+pub use triangulate::{Triangulation, TriangulationMethod, Voronoi};
+pub use validation::Validation;
+
+/// An input geometry as handed to algorithms, in WGS84 lon/lat.
+pub struct NamedGeometry {
+    pub name: String,
+    pub geometry: Geometry<f64>,
+}
+
+/// Everything an algorithm runs on: the current input geometries and the working CRS.
+pub struct InputScene {
+    pub geometries: Vec<NamedGeometry>,
+    /// Index into `geometries` of the input picked in the window, for algorithms that work on a
+    /// single geometry.
+    pub active: usize,
+    /// Index into `geometries` of the second input, for algorithms that compare two geometries.
+    /// Algorithms that need a single probe point use its first vertex.
+    pub secondary: usize,
+    /// Planar algorithms project the geometries into this CRS first.
+    pub working_crs: WorkingCrs,
+}
+
+impl InputScene {
+    pub fn active_geometry(&self) -> Option<&NamedGeometry> {
+        self.geometries.get(self.active)
+    }
+
+    pub fn secondary_geometry(&self) -> Option<&NamedGeometry> {
+        self.geometries.get(self.secondary)
+    }
+
+    /// Every vertex of every input, i.e. all the draggable points.
+    pub fn all_vertices(&self) -> MultiPoint<f64> {
+        self.geometries
+            .iter()
+            .flat_map(|input| input.geometry.coords_iter())
+            .map(Point::from)
+            .collect()
+    }
+
+    pub fn first_line_string(&self) -> Option<&LineString<f64>> {
+        self.line_strings().next().map(|(_, line)| line)
+    }
+
+    pub fn line_strings(&self) -> impl Iterator<Item = (&str, &LineString<f64>)> {
+        self.geometries
+            .iter()
+            .filter_map(|input| match &input.geometry {
+                Geometry::LineString(line) => Some((input.name.as_str(), line)),
+                _ => None,
+            })
+    }
+
+    pub fn polygons(&self) -> impl Iterator<Item = (&str, &Polygon<f64>)> {
+        self.geometries
+            .iter()
+            .filter_map(|input| match &input.geometry {
+                Geometry::Polygon(polygon) => Some((input.name.as_str(), polygon)),
+                _ => None,
+            })
+    }
+
+    /// Two named inputs of the kind `select` accepts, for algorithms that compare a pair: the
+    /// active and the second input when they are distinct and both accepted, and otherwise the
+    /// first two accepted inputs.
+    pub fn pair_of<'a, T>(
+        &'a self,
+        select: impl Fn(&'a Geometry<f64>) -> Option<&'a T>,
+    ) -> Option<((&'a str, &'a T), (&'a str, &'a T))> {
+        let named =
+            |input: &'a NamedGeometry| Some((input.name.as_str(), select(&input.geometry)?));
+        if self.active != self.secondary {
+            let active = self.active_geometry().and_then(&named);
+            let secondary = self.secondary_geometry().and_then(&named);
+            if let (Some(a), Some(b)) = (active, secondary) {
+                return Some((a, b));
+            }
+        }
+        let mut accepted = self.geometries.iter().filter_map(named);
+        Some((accepted.next()?, accepted.next()?))
+    }
+}
+
+/// Projects a geometry from `working_crs` back to lon/lat, e.g. to draw a planar result as an
+/// overlay.
+pub fn from_working_crs<G: MapCoords<f64, f64>>(
+    geometry: &G,
+    working_crs: &WorkingCrs,
+) -> Option<G::Output> {
+    geometry
+        .try_map_coords(|coord| working_crs.inverse(coord).ok_or(()))
+        .ok()
+}
+
+/// The active input projected into the working CRS, or a message saying why there is none.
+pub fn active_in_working_crs(scene: &InputScene) -> Result<Geometry<f64>, String> {
+    let input = scene
+        .active_geometry()
+        .ok_or_else(|| "No input geometry is selected.".to_string())?;
+    to_working_crs(&input.geometry, &scene.working_crs)
+        .ok_or_else(|| format!("{} is outside {}.", input.name, scene.working_crs))
+}
+
+/// Filled overlays for every polygon of a working CRS result.
+pub fn multi_polygon_overlays(
+    polygons: &MultiPolygon<f64>,
+    working_crs: &WorkingCrs,
+    color: Color32,
+) -> Vec<Overlay> {
+    from_working_crs(polygons, working_crs)
+        .map(|polygons| {
+            polygons
+                .into_iter()
+                .map(|polygon| Overlay::polygon(polygon, color))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// The active input in the working CRS, with the number of working CRS units per meter at its
+/// centroid, for algorithms that take a distance in meters.
+pub fn active_with_scale(scene: &InputScene) -> Result<(Geometry<f64>, f64), String> {
+    let geometry = active_in_working_crs(scene)?;
+    let units_per_meter = scene
+        .active_geometry()
+        .and_then(|input| input.geometry.centroid())
+        .and_then(|centroid| scene.working_crs.units_per_meter(centroid.0))
+        .ok_or_else(|| "Cannot convert meters to working CRS units here.".to_string())?;
+    Ok((geometry, units_per_meter))
+}
+
+/// Unfilled overlays for the lines and polygon rings of a lon/lat geometry. They are drawn with
+/// their vertices, and leave the input visible underneath.
+pub fn outline_overlays(geometry: &Geometry<f64>, color: Color32) -> Vec<Overlay> {
+    let polygon_rings = |polygon: &Polygon<f64>| -> Vec<LineString<f64>> {
+        std::iter::once(polygon.exterior())
+            .chain(polygon.interiors())
+            .cloned()
+            .collect()
+    };
+    let lines = match geometry {
+        Geometry::LineString(line) => vec![line.clone()],
+        Geometry::MultiLineString(lines) => lines.0.clone(),
+        Geometry::Polygon(polygon) => polygon_rings(polygon),
+        Geometry::MultiPolygon(polygons) => polygons.iter().flat_map(polygon_rings).collect(),
+        _ => Vec::new(),
+    };
+    lines
+        .into_iter()
+        .map(|line| Overlay::line_string(line, color))
+        .collect()
+}
+
+/// What an algorithm produced: a text summary for the window and shapes to draw on the map.
+pub struct AlgorithmOutput {
+    pub text: Box<dyn Display + Send + Sync>,
+    pub overlays: Vec<Overlay>,
+    /// Shown below the text, for results with several values.
+    pub table: Option<OutputTable>,
+}
+
+impl AlgorithmOutput {
+    pub fn text(text: impl Display + Send + Sync + 'static) -> Self {
+        AlgorithmOutput {
+            text: Box::new(text),
+            overlays: Vec::new(),
+            table: None,
+        }
+    }
+
+    pub fn with_overlays(mut self, overlays: Vec<Overlay>) -> Self {
+        self.overlays = overlays;
+        self
+    }
+
+    pub fn with_table(mut self, table: OutputTable) -> Self {
+        self.table = Some(table);
+        self
+    }
+}
+
+/// One intermediate state recorded by [`Algorithm::trace`], drawn instead of the algorithm's
+/// overlays while the timeline slider is on it.
+pub struct TraceStep {
+    pub description: String,
+    pub overlays: Vec<Overlay>,
+}
+
+impl TraceStep {
+    pub fn new(description: impl Into<String>, overlays: Vec<Overlay>) -> Self {
+        TraceStep {
+            description: description.into(),
+            overlays,
+        }
+    }
+}
+
+pub struct OutputTable {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl OutputTable {
+    pub fn show(&self, ui: &mut Ui, id_salt: impl std::hash::Hash) {
+        egui::Grid::new(id_salt)
+            .striped(true)
+            .num_columns(self.headers.len())
+            .show(ui, |ui| {
+                for header in &self.headers {
+                    ui.strong(header);
+                }
+                ui.end_row();
+                for row in &self.rows {
+                    for cell in row {
+                        ui.monospace(cell);
+                    }
+                    ui.end_row();
+                }
+            });
+    }
+}
+
+/// Projects a lon/lat geometry into `working_crs`, or returns `None` if any coordinate is outside
+/// the projection's domain.
+pub fn to_working_crs<G: MapCoords<f64, f64>>(
+    geometry: &G,
+    working_crs: &WorkingCrs,
+) -> Option<G::Output> {
+    geometry
+        .try_map_coords(|coord| working_crs.forward(coord).ok_or(()))
+        .ok()
+}
+
+/// A demo shown as a section of the window. Each frame the window builds an [`InputScene`] from
+/// the current inputs and hands it to every algorithm, which returns what to display: text, map
+/// overlays and an optional table. The trait is object safe, so the window keeps its algorithms
+/// as `Box<dyn Algorithm>`.
+pub trait Algorithm: Send + Sync + 'static {
+    fn name(&self) -> String;
+    /// Computes the output for `scene`. Runs every frame, and takes `&mut self` so an algorithm
+    /// can keep state between frames, such as an index that is only rebuilt when its inputs
+    /// change.
+    fn calculate_and_box_output(&mut self, scene: &InputScene) -> Option<AlgorithmOutput>;
+    /// Controls for the algorithm's parameters, shown above its output.
+    fn settings_ui(&mut self, _ui: &mut Ui) {}
+    /// Whether the algorithm records its intermediate steps. Algorithms opt in to the timeline by
+    /// returning `true` here and implementing [`Algorithm::trace`].
+    fn has_trace(&self) -> bool {
+        false
+    }
+    /// The steps leading to the output, ending with the output itself. Only runs while the
+    /// algorithm's timeline is open, since recording every step costs more than the result alone.
+    fn trace(&mut self, _scene: &InputScene) -> Result<Vec<TraceStep>, String> {
+        Ok(Vec::new())
+    }
+    /// Whether the algorithm can write its current computation out as a `geo` snippet.
+    fn has_doc_example(&self) -> bool {
+        false
+    }
+    /// A snippet reproducing the current result, built with [`DocExample`]. Like the output, it
+    /// is computed on the inputs as they are now, but only while its panel is open.
+    fn doc_example(&self, _scene: &InputScene) -> Result<String, String> {
+        Err("No example for this algorithm.".to_string())
+    }
+    fn display_ui(&self, ui: &mut Ui, output: &Option<AlgorithmOutput>) {
+        let content = output
+            .as_ref()
+            .map_or_else(|| "N/A".to_string(), |val| val.text.to_string());
+        ui.label(format!("{}: {}", self.name(), content));
+        if let Some(table) = output.as_ref().and_then(|output| output.table.as_ref()) {
+            table.show(ui, self.name());
+        }
+    }
+}
@@ -0,0 +1,274 @@
+//! Ring orientation of the active input, drawn with arrowheads along every ring. GeoJSON's
+//! right-hand rule wants exteriors counter-clockwise and holes clockwise; the window's orientation
+//! buttons rewrite the input to match.
+
+use super::{Algorithm, AlgorithmOutput, DocExample, InputScene, OutputTable};
+use crate::overlay::Overlay;
+use egui::{Color32, Ui};
+use geo::orient::Direction;
+use geo::winding_order::WindingOrder;
+use geo::{Geometry, LineString, Orient as _, Polygon, Winding as _};
+
+const CCW_COLOR: Color32 = Color32::from_rgb(34, 139, 34);
+const CW_COLOR: Color32 = Color32::from_rgb(178, 34, 34);
+const OPEN_COLOR: Color32 = Color32::from_rgb(105, 105, 105);
+
+/// The winding order of each ring of the active input.
+pub struct Winding;
+
+impl Winding {
+    // The active input and each of its rings with its label and winding order, shared by the
+    // output and the snippet.
+    fn compute(
+        scene: &InputScene,
+    ) -> Result<
+        (
+            Geometry<f64>,
+            Vec<(String, LineString<f64>, Option<WindingOrder>)>,
+        ),
+        String,
+    > {
+        let input = scene
+            .active_geometry()
+            .ok_or_else(|| "No input geometry is selected.".to_string())?;
+        let rings = match &input.geometry {
+            Geometry::LineString(line) => vec![("Line".to_string(), line.clone())],
+            Geometry::Polygon(polygon) => polygon_rings(polygon),
+            _ => return Err("Requires a line string or polygon input.".to_string()),
+        };
+        let rings = rings
+            .into_iter()
+            .map(|(label, ring)| {
+                let winding = ring.winding_order();
+                (label, ring, winding)
+            })
+            .collect();
+        Ok((input.geometry.clone(), rings))
+    }
+}
+
+impl Algorithm for Winding {
+    fn name(&self) -> String {
+        "Winding Order".to_string()
+    }
+
+    fn calculate_and_box_output(&mut self, scene: &InputScene) -> Option<AlgorithmOutput> {
+        let (geometry, rings) = match Self::compute(scene) {
+            Ok(result) => result,
+            Err(message) => return Some(AlgorithmOutput::text(message)),
+        };
+
+        let mut follows_rule = true;
+        let mut rows = Vec::new();
+        let mut overlays = Vec::new();
+        for (index, (label, ring, winding)) in rings.iter().enumerate() {
+            let winding = *winding;
+            let expected = match index {
+                0 => WindingOrder::CounterClockwise,
+                _ => WindingOrder::Clockwise,
+            };
+            if matches!(geometry, Geometry::Polygon(_)) && winding != Some(expected) {
+                follows_rule = false;
+            }
+            rows.push(vec![
+                label.clone(),
+                winding_label(winding).to_string(),
+                format!("{}", ring.is_cw()),
+                format!("{}", ring.is_ccw()),
+            ]);
+            overlays.push(Overlay::directed_line(ring.clone(), winding_color(winding)));
+        }
+
+        let text = match geometry {
+            Geometry::Polygon(_) if follows_rule => "follows the right-hand rule",
+            Geometry::Polygon(_) => "does not follow the right-hand rule",
+            _ => "open line",
+        };
+        let table = OutputTable {
+            headers: ["Ring", "Winding", "is_cw", "is_ccw"]
+                .map(String::from)
+                .to_vec(),
+            rows,
+        };
+        Some(
+            AlgorithmOutput::text(text)
+                .with_overlays(overlays)
+                .with_table(table),
+        )
+    }
+
+    fn has_doc_example(&self) -> bool {
+        true
+    }
+
+    fn doc_example(&self, scene: &InputScene) -> Result<String, String> {
+        let (geometry, rings) = Self::compute(scene)?;
+        let windings: Vec<_> = rings.iter().map(|(_, _, winding)| *winding).collect();
+        let example = DocExample::default()
+            .import("Winding")
+            .import("winding_order::WindingOrder");
+        match geometry {
+            Geometry::LineString(line) => Ok(example
+                .geometry("line", line)
+                .assert_eq("line.winding_order()", winding_code(windings[0]))
+                .render()),
+            Geometry::Polygon(polygon) => Ok(polygon_winding_asserts(
+                example.geometry("polygon", polygon),
+                "polygon",
+                &windings,
+            )
+            .render()),
+            _ => Err("Requires a line string or polygon input.".to_string()),
+        }
+    }
+}
+
+/// The active polygon with its rings oriented by `geo`'s `Orient`.
+pub struct Orient {
+    direction: Direction,
+}
+
+impl Default for Orient {
+    fn default() -> Self {
+        Orient {
+            direction: Direction::Default,
+        }
+    }
+}
+
+impl Orient {
+    // The active polygon and its oriented copy, shared by the output and the snippet.
+    fn compute(&self, scene: &InputScene) -> Result<(Polygon<f64>, Polygon<f64>), String> {
+        let Some(Geometry::Polygon(polygon)) = scene.active_geometry().map(|input| &input.geometry)
+        else {
+            return Err("Requires a polygon input.".to_string());
+        };
+        Ok((polygon.clone(), polygon.orient(self.direction)))
+    }
+}
+
+impl Algorithm for Orient {
+    fn name(&self) -> String {
+        "Orient".to_string()
+    }
+
+    fn calculate_and_box_output(&mut self, scene: &InputScene) -> Option<AlgorithmOutput> {
+        let (polygon, oriented) = match self.compute(scene) {
+            Ok(result) => result,
+            Err(message) => return Some(AlgorithmOutput::text(message)),
+        };
+
+        let reversed = polygon_rings(&polygon)
+            .iter()
+            .zip(polygon_rings(&oriented))
+            .filter(|((_, before), (_, after))| before.winding_order() != after.winding_order())
+            .count();
+        let text = format!(
+            "{} of {} rings reversed",
+            reversed,
+            oriented.interiors().len() + 1
+        );
+        let overlays = polygon_rings(&oriented)
+            .into_iter()
+            .map(|(_, ring)| {
+                let color = winding_color(ring.winding_order());
+                Overlay::directed_line(ring, color)
+            })
+            .collect();
+        Some(AlgorithmOutput::text(text).with_overlays(overlays))
+    }
+
+    fn has_doc_example(&self) -> bool {
+        true
+    }
+
+    fn doc_example(&self, scene: &InputScene) -> Result<String, String> {
+        let (polygon, oriented) = self.compute(scene)?;
+        let direction = match self.direction {
+            Direction::Default => "Direction::Default",
+            Direction::Reversed => "Direction::Reversed",
+        };
+        let example = DocExample::default()
+            .import("Orient")
+            .import("Winding")
+            .import("orient::Direction")
+            .import("winding_order::WindingOrder")
+            .geometry("polygon", polygon)
+            .line(format!("let oriented = polygon.orient({});", direction));
+        let windings: Vec<_> = polygon_rings(&oriented)
+            .iter()
+            .map(|(_, ring)| ring.winding_order())
+            .collect();
+        Ok(polygon_winding_asserts(example, "oriented", &windings).render())
+    }
+
+    fn settings_ui(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.radio_value(
+                &mut self.direction,
+                Direction::Default,
+                "Exterior CCW, holes CW",
+            );
+            ui.radio_value(
+                &mut self.direction,
+                Direction::Reversed,
+                "Exterior CW, holes CCW",
+            );
+        });
+    }
+}
+
+fn polygon_rings(polygon: &Polygon<f64>) -> Vec<(String, LineString<f64>)> {
+    std::iter::once(("Exterior".to_string(), polygon.exterior().clone()))
+        .chain(
+            polygon
+                .interiors()
+                .iter()
+                .enumerate()
+                .map(|(i, ring)| (format!("Hole {}", i + 1), ring.clone())),
+        )
+        .collect()
+}
+
+// Asserts the winding order of every ring of the polygon bound in the snippet as `name`, given in
+// the order of `polygon_rings`.
+fn polygon_winding_asserts(
+    example: DocExample,
+    name: &str,
+    windings: &[Option<WindingOrder>],
+) -> DocExample {
+    windings
+        .iter()
+        .enumerate()
+        .fold(example, |example, (index, winding)| {
+            let ring = match index {
+                0 => format!("{}.exterior()", name),
+                _ => format!("{}.interiors()[{}]", name, index - 1),
+            };
+            example.assert_eq(&format!("{}.winding_order()", ring), winding_code(*winding))
+        })
+}
+
+fn winding_code(winding: Option<WindingOrder>) -> &'static str {
+    match winding {
+        Some(WindingOrder::CounterClockwise) => "Some(WindingOrder::CounterClockwise)",
+        Some(WindingOrder::Clockwise) => "Some(WindingOrder::Clockwise)",
+        None => "None",
+    }
+}
+
+fn winding_label(winding: Option<WindingOrder>) -> &'static str {
+    match winding {
+        Some(WindingOrder::CounterClockwise) => "counter-clockwise",
+        Some(WindingOrder::Clockwise) => "clockwise",
+        None => "none",
+    }
+}
+
+fn winding_color(winding: Option<WindingOrder>) -> Color32 {
+    match winding {
+        Some(WindingOrder::CounterClockwise) => CCW_COLOR,
+        Some(WindingOrder::Clockwise) => CW_COLOR,
+        None => OPEN_COLOR,
+    }
+}
@@ -0,0 +1,118 @@
+//! Topological predicates between the active and the second geometry.
+
+use super::{Algorithm, AlgorithmOutput, DocExample, InputScene, OutputTable, to_working_crs};
+use geo::coordinate_position::CoordPos;
+use geo::dimensions::Dimensions;
+use geo::relate::IntersectionMatrix;
+use geo::{Geometry, Relate};
+
+/// Every named predicate of the DE-9IM matrix between the two selected inputs, plus the matrix
+/// itself. The geometries are related in the working CRS, where their edges are straight.
+pub struct PredicateMatrix;
+
+impl Algorithm for PredicateMatrix {
+    fn name(&self) -> String {
+        "Predicates".to_string()
+    }
+
+    fn calculate_and_box_output(&mut self, scene: &InputScene) -> Option<AlgorithmOutput> {
+        let (Some(a), Some(b)) = (scene.active_geometry(), scene.secondary_geometry()) else {
+            return Some(AlgorithmOutput::text("Requires two input geometries."));
+        };
+        let (_, _, matrix) = match related(scene) {
+            Ok(result) => result,
+            Err(message) => return Some(AlgorithmOutput::text(message)),
+        };
+        let predicates = predicates(&matrix);
+
+        let text = format!("{} vs {}: {}", a.name, b.name, de9im_string(&matrix));
+        let table = OutputTable {
+            headers: vec!["Predicate".to_string(), "Result".to_string()],
+            rows: predicates
+                .iter()
+                .map(|(name, _, holds)| {
+                    vec![
+                        format!("{}(A, B)", name),
+                        if *holds { "✔ true" } else { "✘ false" }.to_string(),
+                    ]
+                })
+                .collect(),
+        };
+        Some(AlgorithmOutput::text(text).with_table(table))
+    }
+
+    fn has_doc_example(&self) -> bool {
+        true
+    }
+
+    fn doc_example(&self, scene: &InputScene) -> Result<String, String> {
+        let (a, b, matrix) = related(scene)?;
+        let example = DocExample::default()
+            .import("Relate")
+            .in_working_crs(&scene.working_crs)
+            .geometry("a", a)
+            .geometry("b", b)
+            .line("let matrix = a.relate(&b);");
+        let example = predicates(&matrix)
+            .iter()
+            .fold(example, |example, (_, method, holds)| {
+                let negation = if *holds { "" } else { "!" };
+                example.line(format!("assert!({}matrix.{}());", negation, method))
+            });
+        Ok(example.render())
+    }
+}
+
+// The active and the second input in the working CRS.
+fn two_geometries(scene: &InputScene) -> Result<(Geometry<f64>, Geometry<f64>), String> {
+    let (Some(a), Some(b)) = (scene.active_geometry(), scene.secondary_geometry()) else {
+        return Err("Requires two input geometries.".to_string());
+    };
+    match (
+        to_working_crs(&a.geometry, &scene.working_crs),
+        to_working_crs(&b.geometry, &scene.working_crs),
+    ) {
+        (Some(a), Some(b)) => Ok((a, b)),
+        _ => Err(format!("Inputs are outside {}.", scene.working_crs)),
+    }
+}
+
+// The two inputs and their DE-9IM matrix, shared by the output and the snippet.
+fn related(
+    scene: &InputScene,
+) -> Result<(Geometry<f64>, Geometry<f64>, IntersectionMatrix), String> {
+    let (a, b) = two_geometries(scene)?;
+    let matrix = a.relate(&b);
+    Ok((a, b, matrix))
+}
+
+/// Each named predicate with the `IntersectionMatrix` method that evaluates it.
+fn predicates(matrix: &IntersectionMatrix) -> [(&'static str, &'static str, bool); 10] {
+    [
+        ("Intersects", "is_intersects", matrix.is_intersects()),
+        ("Contains", "is_contains", matrix.is_contains()),
+        ("Within", "is_within", matrix.is_within()),
+        ("Covers", "is_covers", matrix.is_covers()),
+        ("CoveredBy", "is_coveredby", matrix.is_coveredby()),
+        ("Touches", "is_touches", matrix.is_touches()),
+        ("Crosses", "is_crosses", matrix.is_crosses()),
+        ("Overlaps", "is_overlaps", matrix.is_overlaps()),
+        ("Disjoint", "is_disjoint", matrix.is_disjoint()),
+        ("Equals", "is_equal_topo", matrix.is_equal_topo()),
+    ]
+}
+
+// The nine matrix entries in the usual II, IB, IE, BI, ... order, e.g. "212101212".
+fn de9im_string(matrix: &IntersectionMatrix) -> String {
+    let positions = [CoordPos::Inside, CoordPos::OnBoundary, CoordPos::Outside];
+    positions
+        .iter()
+        .flat_map(|a| positions.iter().map(move |b| (*a, *b)))
+        .map(|(a, b)| match matrix.get(a, b) {
+            Dimensions::Empty => 'F',
+            Dimensions::ZeroDimensional => '0',
+            Dimensions::OneDimensional => '1',
+            Dimensions::TwoDimensional => '2',
+        })
+        .collect()
+}
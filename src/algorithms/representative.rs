@@ -0,0 +1,285 @@
+//! Single points and bounding shapes that summarize the active input. All of them are planar, so
+//! they run in the working CRS and their results are unprojected for display.
+
+use super::{
+    Algorithm, AlgorithmOutput, DocExample, InputScene, active_in_working_crs, binding_name,
+    from_working_crs,
+};
+use crate::crs::WorkingCrs;
+use crate::overlay::Overlay;
+use egui::Color32;
+use geo::{
+    Area, BoundingRect as _, Centroid as _, Coord, Extremes as _, Geometry, InteriorPoint as _,
+    MinimumRotatedRect as _, Point, Polygon,
+};
+
+const CENTROID_COLOR: Color32 = Color32::from_rgb(220, 20, 60);
+const INTERIOR_POINT_COLOR: Color32 = Color32::from_rgb(0, 160, 80);
+const BOUNDING_RECT_COLOR: Color32 = Color32::from_rgb(90, 90, 90);
+const MINIMUM_ROTATED_RECT_COLOR: Color32 = Color32::from_rgb(150, 60, 200);
+const EXTREMES_COLOR: Color32 = Color32::from_rgb(230, 120, 0);
+
+pub struct Centroid;
+
+impl Algorithm for Centroid {
+    fn name(&self) -> String {
+        "Centroid".to_string()
+    }
+
+    fn calculate_and_box_output(&mut self, scene: &InputScene) -> Option<AlgorithmOutput> {
+        Some(point_output(
+            active_point(scene, |geometry| geometry.centroid()),
+            &scene.working_crs,
+            CENTROID_COLOR,
+        ))
+    }
+
+    fn has_doc_example(&self) -> bool {
+        true
+    }
+
+    fn doc_example(&self, scene: &InputScene) -> Result<String, String> {
+        let (geometry, centroid) = active_point(scene, |geometry| geometry.centroid())?;
+        Ok(active_example(scene, &geometry, "Centroid")
+            .line(format!(
+                "let centroid = {}.centroid().unwrap();",
+                binding_name(&geometry)
+            ))
+            .assert_point_close("centroid", centroid)
+            .render())
+    }
+}
+
+/// A point guaranteed to lie inside the geometry, unlike the centroid of a concave polygon.
+pub struct InteriorPoint;
+
+impl Algorithm for InteriorPoint {
+    fn name(&self) -> String {
+        "Interior Point".to_string()
+    }
+
+    fn calculate_and_box_output(&mut self, scene: &InputScene) -> Option<AlgorithmOutput> {
+        Some(point_output(
+            active_point(scene, |geometry| geometry.interior_point()),
+            &scene.working_crs,
+            INTERIOR_POINT_COLOR,
+        ))
+    }
+
+    fn has_doc_example(&self) -> bool {
+        true
+    }
+
+    fn doc_example(&self, scene: &InputScene) -> Result<String, String> {
+        let (geometry, interior_point) = active_point(scene, |geometry| geometry.interior_point())?;
+        Ok(active_example(scene, &geometry, "InteriorPoint")
+            .line(format!(
+                "let interior_point = {}.interior_point().unwrap();",
+                binding_name(&geometry)
+            ))
+            .assert_point_close("interior_point", interior_point)
+            .render())
+    }
+}
+
+/// Axis-aligned bounding rectangle. The axes are those of the working CRS.
+pub struct BoundingRect;
+
+impl Algorithm for BoundingRect {
+    fn name(&self) -> String {
+        "Bounding Rect".to_string()
+    }
+
+    fn calculate_and_box_output(&mut self, scene: &InputScene) -> Option<AlgorithmOutput> {
+        let rect = match active_result(scene, |geometry| geometry.bounding_rect()) {
+            Ok((_, rect)) => rect,
+            Err(message) => return Some(AlgorithmOutput::text(message)),
+        };
+        let unit = scene.working_crs.unit_suffix();
+        let text = format!(
+            "{:.2} {} x {:.2} {}",
+            rect.width(),
+            unit,
+            rect.height(),
+            unit
+        );
+        Some(rect_output(
+            text,
+            &rect.to_polygon(),
+            &scene.working_crs,
+            BOUNDING_RECT_COLOR,
+        ))
+    }
+
+    fn has_doc_example(&self) -> bool {
+        true
+    }
+
+    fn doc_example(&self, scene: &InputScene) -> Result<String, String> {
+        let (geometry, rect) = active_result(scene, |geometry| geometry.bounding_rect())?;
+        Ok(active_example(scene, &geometry, "BoundingRect")
+            .line(format!(
+                "let rect = {}.bounding_rect().unwrap();",
+                binding_name(&geometry)
+            ))
+            .assert_close("rect.width()", rect.width())
+            .assert_close("rect.height()", rect.height())
+            .render())
+    }
+}
+
+/// Smallest rectangle of any orientation that contains the geometry.
+pub struct MinimumRotatedRect;
+
+impl Algorithm for MinimumRotatedRect {
+    fn name(&self) -> String {
+        "Minimum Rotated Rect".to_string()
+    }
+
+    fn calculate_and_box_output(&mut self, scene: &InputScene) -> Option<AlgorithmOutput> {
+        let rect = match active_result(scene, |geometry| geometry.minimum_rotated_rect()) {
+            Ok((_, rect)) => rect,
+            Err(message) => return Some(AlgorithmOutput::text(message)),
+        };
+        let text = format!(
+            "area {:.2} {}²",
+            rect.unsigned_area(),
+            scene.working_crs.unit_suffix()
+        );
+        Some(rect_output(
+            text,
+            &rect,
+            &scene.working_crs,
+            MINIMUM_ROTATED_RECT_COLOR,
+        ))
+    }
+
+    fn has_doc_example(&self) -> bool {
+        true
+    }
+
+    fn doc_example(&self, scene: &InputScene) -> Result<String, String> {
+        let (geometry, rect) = active_result(scene, |geometry| geometry.minimum_rotated_rect())?;
+        Ok(active_example(scene, &geometry, "MinimumRotatedRect")
+            .import("Area")
+            .line(format!(
+                "let rect = {}.minimum_rotated_rect().unwrap();",
+                binding_name(&geometry)
+            ))
+            .assert_close("rect.unsigned_area()", rect.unsigned_area())
+            .render())
+    }
+}
+
+/// The vertices with the smallest and largest x and y in the working CRS.
+pub struct Extremes;
+
+impl Algorithm for Extremes {
+    fn name(&self) -> String {
+        "Extremes".to_string()
+    }
+
+    fn calculate_and_box_output(&mut self, scene: &InputScene) -> Option<AlgorithmOutput> {
+        let outcome = match active_result(scene, |geometry| geometry.extremes()) {
+            Ok((_, outcome)) => outcome,
+            Err(message) => return Some(AlgorithmOutput::text(message)),
+        };
+        let extremes = [
+            ("min x", outcome.x_min),
+            ("max x", outcome.x_max),
+            ("min y", outcome.y_min),
+            ("max y", outcome.y_max),
+        ];
+
+        let text = extremes
+            .iter()
+            .map(|(label, extreme)| format!("{} #{}", label, extreme.index))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let overlays = extremes
+            .iter()
+            .filter_map(|(label, extreme)| {
+                let position = scene.working_crs.inverse(extreme.coord)?;
+                Some(Overlay::labeled_marker(position, *label, EXTREMES_COLOR))
+            })
+            .collect();
+        Some(AlgorithmOutput::text(text).with_overlays(overlays))
+    }
+
+    fn has_doc_example(&self) -> bool {
+        true
+    }
+
+    fn doc_example(&self, scene: &InputScene) -> Result<String, String> {
+        let (geometry, outcome) = active_result(scene, |geometry| geometry.extremes())?;
+        Ok(active_example(scene, &geometry, "Extremes")
+            .line(format!(
+                "let extremes = {}.extremes().unwrap();",
+                binding_name(&geometry)
+            ))
+            .assert_eq("extremes.x_min.index", outcome.x_min.index)
+            .assert_eq("extremes.x_max.index", outcome.x_max.index)
+            .assert_eq("extremes.y_min.index", outcome.y_min.index)
+            .assert_eq("extremes.y_max.index", outcome.y_max.index)
+            .render())
+    }
+}
+
+// The active input in the working CRS with `compute` applied to it, shared by the output and the
+// snippet so they cannot disagree. `compute` returns `None` for an empty geometry.
+fn active_result<T>(
+    scene: &InputScene,
+    compute: impl Fn(&Geometry<f64>) -> Option<T>,
+) -> Result<(Geometry<f64>, T), String> {
+    let geometry = active_in_working_crs(scene)?;
+    let result = compute(&geometry).ok_or_else(|| "The geometry is empty.".to_string())?;
+    Ok((geometry, result))
+}
+
+// Like `active_result`, for point results that can be undefined even for a non-empty geometry.
+fn active_point(
+    scene: &InputScene,
+    compute: impl Fn(&Geometry<f64>) -> Option<Point<f64>>,
+) -> Result<(Geometry<f64>, Coord<f64>), String> {
+    let geometry = active_in_working_crs(scene)?;
+    let point = compute(&geometry).ok_or_else(|| "Undefined for this geometry.".to_string())?;
+    Ok((geometry, point.0))
+}
+
+// A snippet importing `algorithm` and binding the active input in the working CRS.
+fn active_example(scene: &InputScene, geometry: &Geometry<f64>, algorithm: &str) -> DocExample {
+    DocExample::default()
+        .import(algorithm)
+        .in_working_crs(&scene.working_crs)
+        .geometry(binding_name(geometry), geometry.clone())
+}
+
+// Shows a working CRS point as lon/lat text with a marker.
+fn point_output(
+    point: Result<(Geometry<f64>, Coord<f64>), String>,
+    working_crs: &WorkingCrs,
+    color: Color32,
+) -> AlgorithmOutput {
+    let (_, point) = match point {
+        Ok(point) => point,
+        Err(message) => return AlgorithmOutput::text(message),
+    };
+    match working_crs.inverse(point) {
+        Some(position) => AlgorithmOutput::text(format!("{:.6}, {:.6}", position.x, position.y))
+            .with_overlays(vec![Overlay::marker(position, color)]),
+        None => AlgorithmOutput::text("Undefined for this geometry."),
+    }
+}
+
+// Shows a working CRS rectangle as a dashed outline.
+fn rect_output(
+    text: String,
+    rect: &Polygon<f64>,
+    working_crs: &WorkingCrs,
+    color: Color32,
+) -> AlgorithmOutput {
+    let overlays = from_working_crs(rect, working_crs)
+        .map(|rect| vec![Overlay::dashed_ring(rect.exterior().0.clone(), color)])
+        .unwrap_or_default();
+    AlgorithmOutput::text(text).with_overlays(overlays)
+}
@@ -0,0 +1,217 @@
+//! Similarity measures between two line string inputs: the active and the second input when both
+//! are line strings, and the first two line strings otherwise. Both measures are planar, so the
+//! lines are compared in the working CRS.
+
+use super::{Algorithm, AlgorithmOutput, DocExample, InputScene, to_working_crs};
+use crate::crs::WorkingCrs;
+use crate::overlay::Overlay;
+use egui::Color32;
+use geo::{
+    Distance, Euclidean, FrechetDistance as _, Geometry, HausdorffDistance as _, LineString, Point,
+};
+
+const HAUSDORFF_COLOR: Color32 = Color32::from_rgb(210, 105, 30);
+const FRECHET_COLOR: Color32 = Color32::from_rgb(72, 61, 139);
+
+pub struct HausdorffDistance;
+
+impl Algorithm for HausdorffDistance {
+    fn name(&self) -> String {
+        "Hausdorff Distance".to_string()
+    }
+
+    fn calculate_and_box_output(&mut self, scene: &InputScene) -> Option<AlgorithmOutput> {
+        let (a, b, distance) = match measured_lines(scene, |a, b| a.hausdorff_distance(b)) {
+            Ok(measured) => measured,
+            Err(message) => return Some(AlgorithmOutput::text(message)),
+        };
+
+        // geo only returns the distance, which it measures between vertices. The realizing pair
+        // is the vertex of either line that is farthest from the other line's vertices, together
+        // with its nearest vertex on that line.
+        let pair = farthest_vertex(&a, &b)
+            .into_iter()
+            .chain(farthest_vertex(&b, &a))
+            .max_by(|x, y| x.2.total_cmp(&y.2))
+            .map(|(from, to, _)| (from, to));
+        Some(pair_output(
+            distance,
+            pair,
+            &scene.working_crs,
+            HAUSDORFF_COLOR,
+        ))
+    }
+
+    fn has_doc_example(&self) -> bool {
+        true
+    }
+
+    fn doc_example(&self, scene: &InputScene) -> Result<String, String> {
+        let (a, b, distance) = measured_lines(scene, |a, b| a.hausdorff_distance(b))?;
+        Ok(two_lines_example(scene, a, b, "HausdorffDistance")
+            .line("let distance = a.hausdorff_distance(&b);")
+            .assert_close("distance", distance)
+            .render())
+    }
+}
+
+/// Discrete Fréchet distance, which unlike Hausdorff respects the direction of both lines.
+pub struct FrechetDistance;
+
+impl Algorithm for FrechetDistance {
+    fn name(&self) -> String {
+        "Fréchet Distance".to_string()
+    }
+
+    fn calculate_and_box_output(&mut self, scene: &InputScene) -> Option<AlgorithmOutput> {
+        let (a, b, distance) = match measured_lines(scene, |a, b| a.frechet_distance(b)) {
+            Ok(measured) => measured,
+            Err(message) => return Some(AlgorithmOutput::text(message)),
+        };
+
+        let pair = frechet_bottleneck(&a, &b);
+        Some(pair_output(
+            distance,
+            pair,
+            &scene.working_crs,
+            FRECHET_COLOR,
+        ))
+    }
+
+    fn has_doc_example(&self) -> bool {
+        true
+    }
+
+    fn doc_example(&self, scene: &InputScene) -> Result<String, String> {
+        let (a, b, distance) = measured_lines(scene, |a, b| a.frechet_distance(b))?;
+        Ok(two_lines_example(scene, a, b, "FrechetDistance")
+            .line("let distance = a.frechet_distance(&b);")
+            .assert_close("distance", distance)
+            .render())
+    }
+}
+
+// The pair of line string inputs in the working CRS.
+fn two_lines(scene: &InputScene) -> Result<(LineString<f64>, LineString<f64>), String> {
+    let ((_, a), (_, b)) = scene
+        .pair_of(|geometry| match geometry {
+            Geometry::LineString(line) => Some(line),
+            _ => None,
+        })
+        .ok_or_else(|| "Requires two line string inputs.".to_string())?;
+    match (
+        to_working_crs(a, &scene.working_crs),
+        to_working_crs(b, &scene.working_crs),
+    ) {
+        (Some(a), Some(b)) if !a.0.is_empty() && !b.0.is_empty() => Ok((a, b)),
+        (Some(_), Some(_)) => Err("Both lines need at least one vertex.".to_string()),
+        _ => Err(format!("Lines are outside {}.", scene.working_crs)),
+    }
+}
+
+// The pair of lines with `measure` applied to them, shared by the output and the snippet.
+fn measured_lines(
+    scene: &InputScene,
+    measure: impl Fn(&LineString<f64>, &LineString<f64>) -> f64,
+) -> Result<(LineString<f64>, LineString<f64>, f64), String> {
+    let (a, b) = two_lines(scene)?;
+    let distance = measure(&a, &b);
+    Ok((a, b, distance))
+}
+
+// A snippet importing `algorithm` and binding both lines in the working CRS.
+fn two_lines_example(
+    scene: &InputScene,
+    a: LineString<f64>,
+    b: LineString<f64>,
+    algorithm: &str,
+) -> DocExample {
+    DocExample::default()
+        .import(algorithm)
+        .in_working_crs(&scene.working_crs)
+        .geometry("a", a)
+        .geometry("b", b)
+}
+
+// The vertex of `from` farthest from the vertices of `to`, its nearest vertex on `to`, and
+// their distance.
+fn farthest_vertex(
+    from: &LineString<f64>,
+    to: &LineString<f64>,
+) -> Option<(Point<f64>, Point<f64>, f64)> {
+    from.points()
+        .filter_map(|vertex| {
+            to.points()
+                .map(|other| (vertex, other, Euclidean.distance(vertex, other)))
+                .min_by(|x, y| x.2.total_cmp(&y.2))
+        })
+        .max_by(|x, y| x.2.total_cmp(&y.2))
+}
+
+// The pair of vertices that realizes the discrete Fréchet distance. Recomputes the coupling
+// table geo uses internally, then walks an optimal coupling back from the last pair and keeps
+// its longest link.
+fn frechet_bottleneck(
+    a: &LineString<f64>,
+    b: &LineString<f64>,
+) -> Option<(Point<f64>, Point<f64>)> {
+    let (a, b): (Vec<Point<f64>>, Vec<Point<f64>>) = (a.points().collect(), b.points().collect());
+    if a.is_empty() || b.is_empty() {
+        return None;
+    }
+    let width = b.len();
+    let mut coupling = vec![0.0; a.len() * width];
+    for (i, a_vertex) in a.iter().enumerate() {
+        for (j, b_vertex) in b.iter().enumerate() {
+            let link = Euclidean.distance(*a_vertex, *b_vertex);
+            let previous = match (i, j) {
+                (0, 0) => 0.0,
+                (0, _) => coupling[j - 1],
+                (_, 0) => coupling[(i - 1) * width],
+                _ => coupling[(i - 1) * width + j]
+                    .min(coupling[(i - 1) * width + j - 1])
+                    .min(coupling[i * width + j - 1]),
+            };
+            coupling[i * width + j] = link.max(previous);
+        }
+    }
+
+    let (mut i, mut j) = (a.len() - 1, width - 1);
+    let mut longest = (i, j, Euclidean.distance(a[i], b[j]));
+    while i > 0 || j > 0 {
+        (i, j) = match (i, j) {
+            (0, _) => (0, j - 1),
+            (_, 0) => (i - 1, 0),
+            _ => [(i - 1, j - 1), (i - 1, j), (i, j - 1)]
+                .into_iter()
+                .min_by(|x, y| {
+                    coupling[x.0 * width + x.1].total_cmp(&coupling[y.0 * width + y.1])
+                })?,
+        };
+        let link = Euclidean.distance(a[i], b[j]);
+        if link > longest.2 {
+            longest = (i, j, link);
+        }
+    }
+    Some((a[longest.0], b[longest.1]))
+}
+
+fn pair_output(
+    distance: f64,
+    pair: Option<(Point<f64>, Point<f64>)>,
+    working_crs: &WorkingCrs,
+    color: Color32,
+) -> AlgorithmOutput {
+    let text = format!("{:.2} {}", distance, working_crs.unit_suffix());
+    let overlays = pair
+        .and_then(|(from, to)| Some((working_crs.inverse(from.0)?, working_crs.inverse(to.0)?)))
+        .map(|(from, to)| {
+            vec![
+                Overlay::connector(from, to, color),
+                Overlay::marker(from, color),
+                Overlay::marker(to, color),
+            ]
+        })
+        .unwrap_or_default();
+    AlgorithmOutput::text(text).with_overlays(overlays)
+}
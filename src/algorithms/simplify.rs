@@ -0,0 +1,330 @@
+//! Line simplification of the active input. The three methods are registered side by side, each in
+//! its own color, so their results can be compared over the original geometry.
+//!
+//! RDP and Visvalingam-Whyatt can also be stepped through. The traces rerun the algorithm on the
+//! line, or on a polygon's exterior ring, and record every vertex it keeps or drops.
+
+use super::{
+    Algorithm, AlgorithmOutput, DocExample, InputScene, TraceStep, active_in_working_crs,
+    binding_name, from_working_crs, outline_overlays,
+};
+use crate::overlay::Overlay;
+use egui::{Color32, Ui};
+use geo::{
+    Area, Coord, CoordsIter, Distance, Euclidean, Geometry, Line, LineString, Point, Polygon,
+    Simplify, SimplifyVw, SimplifyVwPreserve, Triangle,
+};
+
+const CHORD_COLOR: Color32 = Color32::from_rgb(105, 105, 105);
+const KEPT_COLOR: Color32 = Color32::from_rgb(34, 139, 34);
+const DROPPED_COLOR: Color32 = Color32::from_rgb(178, 34, 34);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SimplifyMethod {
+    /// Ramer-Douglas-Peucker. Epsilon is a distance.
+    Rdp,
+    /// Visvalingam-Whyatt. Epsilon is a triangle area.
+    Vw,
+    /// Visvalingam-Whyatt that never introduces self-intersections.
+    VwPreserve,
+}
+
+pub struct SimplifyLine {
+    method: SimplifyMethod,
+    /// In working CRS units for RDP and squared units for the Visvalingam-Whyatt methods.
+    epsilon: f64,
+}
+
+impl SimplifyLine {
+    pub fn new(method: SimplifyMethod) -> Self {
+        let epsilon = match method {
+            SimplifyMethod::Rdp => 5_000.0,
+            SimplifyMethod::Vw | SimplifyMethod::VwPreserve => 1e8,
+        };
+        SimplifyLine { method, epsilon }
+    }
+
+    fn color(&self) -> Color32 {
+        match self.method {
+            SimplifyMethod::Rdp => Color32::from_rgb(228, 26, 28),
+            SimplifyMethod::Vw => Color32::from_rgb(77, 175, 74),
+            SimplifyMethod::VwPreserve => Color32::from_rgb(152, 78, 163),
+        }
+    }
+
+    // The active input in the working CRS and its simplified copy, shared by the output and the
+    // snippet.
+    fn simplify(&self, scene: &InputScene) -> Result<(Geometry<f64>, Geometry<f64>), String> {
+        let geometry = active_in_working_crs(scene)?;
+        let epsilon = self.epsilon;
+        let simplified = match (self.method, &geometry) {
+            (SimplifyMethod::Rdp, Geometry::LineString(line)) => line.simplify(epsilon).into(),
+            (SimplifyMethod::Rdp, Geometry::Polygon(polygon)) => polygon.simplify(epsilon).into(),
+            (SimplifyMethod::Vw, Geometry::LineString(line)) => line.simplify_vw(epsilon).into(),
+            (SimplifyMethod::Vw, Geometry::Polygon(polygon)) => polygon.simplify_vw(epsilon).into(),
+            (SimplifyMethod::VwPreserve, Geometry::LineString(line)) => {
+                line.simplify_vw_preserve(epsilon).into()
+            }
+            (SimplifyMethod::VwPreserve, Geometry::Polygon(polygon)) => {
+                polygon.simplify_vw_preserve(epsilon).into()
+            }
+            _ => return Err("Requires a line string or polygon input.".to_string()),
+        };
+        Ok((geometry, simplified))
+    }
+
+    /// Ramer-Douglas-Peucker, depth first like the recursion in `geo`: each step tests the chord
+    /// between two kept vertices and keeps the farthest vertex between them if it is further than
+    /// epsilon, or drops all of them otherwise.
+    fn rdp_trace(
+        &self,
+        lonlat: &[Coord<f64>],
+        projected: &[Coord<f64>],
+        unit: &str,
+    ) -> Vec<TraceStep> {
+        let mut kept = vec![false; projected.len()];
+        kept[0] = true;
+        kept[projected.len() - 1] = true;
+        let mut steps = vec![self.line_step(
+            format!("Start with {} vertices, keeping both ends", projected.len()),
+            lonlat,
+            &kept,
+        )];
+        let mut pending = vec![(0, projected.len() - 1)];
+        while let Some((start, end)) = pending.pop() {
+            if end - start < 2 {
+                continue;
+            }
+            let chord = Line::new(projected[start], projected[end]);
+            let mut farthest = (start + 1, 0.0);
+            for (i, coord) in projected.iter().enumerate().take(end).skip(start + 1) {
+                let distance = Euclidean.distance(&Point(*coord), &chord);
+                if distance > farthest.1 {
+                    farthest = (i, distance);
+                }
+            }
+            let (index, distance) = farthest;
+            let keep = distance > self.epsilon;
+            let description = if keep {
+                kept[index] = true;
+                // Pushed end side first, so the start side is traced first.
+                pending.push((index, end));
+                pending.push((start, index));
+                format!(
+                    "Chord {}-{}: vertex {} is {:.0} {} away, keep it",
+                    start, end, index, distance, unit
+                )
+            } else {
+                format!(
+                    "Chord {}-{}: vertex {} is {:.0} {} away, drop {} vertices",
+                    start,
+                    end,
+                    index,
+                    distance,
+                    unit,
+                    end - start - 1
+                )
+            };
+            let mut step = self.line_step(description, lonlat, &kept);
+            step.overlays.extend([
+                Overlay::connector(lonlat[start], lonlat[end], CHORD_COLOR),
+                Overlay::labeled_marker(
+                    lonlat[index],
+                    format!("{:.0} {}", distance, unit),
+                    if keep { KEPT_COLOR } else { DROPPED_COLOR },
+                ),
+            ]);
+            steps.push(step);
+        }
+        steps.push(self.result_step(lonlat, &kept));
+        steps
+    }
+
+    /// Visvalingam-Whyatt: each step drops the vertex whose triangle with its two neighbours has
+    /// the smallest area, until every remaining triangle is at least epsilon.
+    fn vw_trace(
+        &self,
+        lonlat: &[Coord<f64>],
+        projected: &[Coord<f64>],
+        unit: &str,
+    ) -> Vec<TraceStep> {
+        let mut kept = vec![true; projected.len()];
+        let mut steps = vec![self.line_step(
+            format!("Start with {} vertices", projected.len()),
+            lonlat,
+            &kept,
+        )];
+        loop {
+            let remaining: Vec<usize> = (0..kept.len()).filter(|i| kept[*i]).collect();
+            let smallest = remaining
+                .windows(3)
+                .map(|triangle| {
+                    let [a, b, c] = [triangle[0], triangle[1], triangle[2]];
+                    let area =
+                        Triangle::new(projected[a], projected[b], projected[c]).unsigned_area();
+                    ([a, b, c], area)
+                })
+                .min_by(|x, y| x.1.total_cmp(&y.1));
+            let Some(([a, b, c], area)) = smallest.filter(|(_, area)| *area < self.epsilon) else {
+                break;
+            };
+            let mut step = self.line_step(
+                format!(
+                    "Vertex {} has the smallest triangle, {:.3e} {}², drop it",
+                    b, area, unit
+                ),
+                lonlat,
+                &kept,
+            );
+            step.overlays.extend([
+                Overlay::polygon(
+                    Polygon::new(
+                        LineString::from(vec![lonlat[a], lonlat[b], lonlat[c]]),
+                        Vec::new(),
+                    ),
+                    DROPPED_COLOR,
+                ),
+                Overlay::marker(lonlat[b], DROPPED_COLOR),
+            ]);
+            steps.push(step);
+            kept[b] = false;
+        }
+        steps.push(self.result_step(lonlat, &kept));
+        steps
+    }
+
+    /// A step showing the line through the vertices kept so far.
+    fn line_step(&self, description: String, lonlat: &[Coord<f64>], kept: &[bool]) -> TraceStep {
+        let line: LineString<f64> = lonlat
+            .iter()
+            .zip(kept)
+            .filter(|(_, kept)| **kept)
+            .map(|(coord, _)| *coord)
+            .collect();
+        TraceStep::new(description, vec![Overlay::line_string(line, self.color())])
+    }
+
+    fn result_step(&self, lonlat: &[Coord<f64>], kept: &[bool]) -> TraceStep {
+        let count = kept.iter().filter(|kept| **kept).count();
+        self.line_step(
+            format!("Result: {} of {} vertices kept", count, kept.len()),
+            lonlat,
+            kept,
+        )
+    }
+}
+
+/// The active line, or the exterior ring of the active polygon, in lon/lat and in the working CRS
+/// with the same vertices, so a trace can compute on one and draw the other.
+fn traced_line(scene: &InputScene) -> Result<(Vec<Coord<f64>>, Vec<Coord<f64>>), String> {
+    let projected = active_in_working_crs(scene)?;
+    let line = |geometry: &Geometry<f64>| match geometry {
+        Geometry::LineString(line) => Some(line.0.clone()),
+        Geometry::Polygon(polygon) => Some(polygon.exterior().0.clone()),
+        _ => None,
+    };
+    let lonlat = scene
+        .active_geometry()
+        .and_then(|input| line(&input.geometry));
+    match (lonlat, line(&projected)) {
+        (Some(lonlat), Some(projected)) if projected.len() >= 2 => Ok((lonlat, projected)),
+        (Some(_), Some(_)) => Err("Requires at least two vertices.".to_string()),
+        _ => Err("Requires a line string or polygon input.".to_string()),
+    }
+}
+
+impl Algorithm for SimplifyLine {
+    fn name(&self) -> String {
+        match self.method {
+            SimplifyMethod::Rdp => "Simplify (RDP)",
+            SimplifyMethod::Vw => "Simplify (Visvalingam-Whyatt)",
+            SimplifyMethod::VwPreserve => "Simplify (VW, topology-preserving)",
+        }
+        .to_string()
+    }
+
+    fn calculate_and_box_output(&mut self, scene: &InputScene) -> Option<AlgorithmOutput> {
+        let (geometry, simplified) = match self.simplify(scene) {
+            Ok(result) => result,
+            Err(message) => return Some(AlgorithmOutput::text(message)),
+        };
+
+        let text = format!(
+            "{} -> {} vertices",
+            geometry.coords_count(),
+            simplified.coords_count()
+        );
+        let overlays = from_working_crs(&simplified, &scene.working_crs)
+            .map(|simplified| outline_overlays(&simplified, self.color()))
+            .unwrap_or_default();
+        Some(AlgorithmOutput::text(text).with_overlays(overlays))
+    }
+
+    fn settings_ui(&mut self, ui: &mut Ui) {
+        let (max, label) = match self.method {
+            SimplifyMethod::Rdp => (1e5, "Epsilon"),
+            SimplifyMethod::Vw | SimplifyMethod::VwPreserve => (1e10, "Epsilon (area)"),
+        };
+        ui.add(
+            egui::Slider::new(&mut self.epsilon, 0.0..=max)
+                .logarithmic(true)
+                .text(label),
+        );
+    }
+
+    fn has_trace(&self) -> bool {
+        matches!(self.method, SimplifyMethod::Rdp | SimplifyMethod::Vw)
+    }
+
+    fn trace(&mut self, scene: &InputScene) -> Result<Vec<TraceStep>, String> {
+        let (lonlat, projected) = traced_line(scene)?;
+        let unit = scene.working_crs.unit_suffix();
+        let mut steps = match self.method {
+            SimplifyMethod::Rdp => self.rdp_trace(&lonlat, &projected, unit),
+            SimplifyMethod::Vw => self.vw_trace(&lonlat, &projected, unit),
+            SimplifyMethod::VwPreserve => Vec::new(),
+        };
+        // Only the exterior of a polygon is traced, so say so rather than let the result step
+        // pass for the whole output.
+        if let Some(Geometry::Polygon(polygon)) =
+            scene.active_geometry().map(|input| &input.geometry)
+        {
+            for step in &mut steps {
+                step.description = format!("Exterior ring: {}", step.description);
+            }
+            let holes = polygon.interiors().len();
+            if let Some(result) = steps.last_mut().filter(|_| holes > 0) {
+                result.description += &format!(
+                    " (the output also simplifies {} holes, which are not traced)",
+                    holes
+                );
+            }
+        }
+        Ok(steps)
+    }
+
+    fn has_doc_example(&self) -> bool {
+        true
+    }
+
+    fn doc_example(&self, scene: &InputScene) -> Result<String, String> {
+        let (geometry, simplified) = self.simplify(scene)?;
+        let (import, method) = match self.method {
+            SimplifyMethod::Rdp => ("Simplify", "simplify"),
+            SimplifyMethod::Vw => ("SimplifyVw", "simplify_vw"),
+            SimplifyMethod::VwPreserve => ("SimplifyVwPreserve", "simplify_vw_preserve"),
+        };
+        let name = binding_name(&geometry);
+        Ok(DocExample::default()
+            .import(import)
+            .import("CoordsIter")
+            .in_working_crs(&scene.working_crs)
+            .geometry(name, geometry.clone())
+            .line(format!(
+                "let simplified = {}.{}({:?});",
+                name, method, self.epsilon
+            ))
+            .assert_eq("simplified.coords_count()", simplified.coords_count())
+            .render())
+    }
+}
@@ -0,0 +1,239 @@
+//! An R-tree over thousands of generated points, queried from the probe point. The tree is kept
+//! between frames and only rebuilt when the point set or the working CRS changes, so dragging the
+//! probe measures the queries alone.
+
+use std::sync::Arc;
+
+use super::{Algorithm, AlgorithmOutput, InputScene, OutputTable, from_working_crs};
+use crate::crs::WorkingCrs;
+use crate::overlay::Overlay;
+use crate::random::SeededRng;
+use egui::{Color32, Ui};
+use geo::{Coord, CoordsIter, LineString};
+use rstar::RTree;
+use rstar::primitives::GeomWithData;
+use web_time::{Duration, Instant};
+
+const POINT_COLOR: Color32 = Color32::from_rgb(128, 128, 128);
+const NEAREST_COLOR: Color32 = Color32::from_rgb(255, 0, 255);
+const WITHIN_COLOR: Color32 = Color32::from_rgb(0, 191, 255);
+
+/// Lon/lat bounds the points are scattered over, around the default inputs.
+const MIN_LONLAT: Coord<f64> = Coord { x: 127.0, y: 36.5 };
+const MAX_LONLAT: Coord<f64> = Coord { x: 130.0, y: 39.0 };
+
+/// A generated point in the working CRS, with its index into the lon/lat points.
+type IndexedPoint = GeomWithData<[f64; 2], usize>;
+
+struct BuiltIndex {
+    point_count: usize,
+    seed: u64,
+    working_crs: WorkingCrs,
+    /// Shared with the background point cloud overlay, which is handed out every frame.
+    lonlat: Arc<[Coord<f64>]>,
+    tree: RTree<IndexedPoint>,
+    build_time: Duration,
+}
+
+pub struct SpatialIndex {
+    pub point_count: usize,
+    pub seed: u64,
+    /// Number of nearest neighbours to highlight.
+    pub k: usize,
+    pub radius_meters: f64,
+    index: Option<BuiltIndex>,
+}
+
+impl Default for SpatialIndex {
+    fn default() -> Self {
+        SpatialIndex {
+            point_count: 5_000,
+            seed: 1,
+            k: 10,
+            radius_meters: 20_000.0,
+            index: None,
+        }
+    }
+}
+
+impl SpatialIndex {
+    /// The tree for the current settings, rebuilt if anything it depends on has changed.
+    fn index(&mut self, working_crs: WorkingCrs) -> &BuiltIndex {
+        let (point_count, seed) = (self.point_count, self.seed);
+        let stale = self.index.as_ref().is_none_or(|index| {
+            index.point_count != point_count
+                || index.seed != seed
+                || index.working_crs != working_crs
+        });
+        if stale {
+            self.index = Some(build_index(point_count, seed, working_crs));
+        }
+        self.index.as_ref().unwrap()
+    }
+}
+
+fn build_index(point_count: usize, seed: u64, working_crs: WorkingCrs) -> BuiltIndex {
+    let mut rng = SeededRng::new(seed);
+    let lonlat: Vec<Coord<f64>> = (0..point_count)
+        .map(|_| Coord {
+            x: rng.range(MIN_LONLAT.x, MAX_LONLAT.x),
+            y: rng.range(MIN_LONLAT.y, MAX_LONLAT.y),
+        })
+        .collect();
+
+    let start = Instant::now();
+    let points = lonlat
+        .iter()
+        .enumerate()
+        .filter_map(|(i, coord)| {
+            let projected = working_crs.forward(*coord)?;
+            Some(IndexedPoint::new([projected.x, projected.y], i))
+        })
+        .collect();
+    let tree = RTree::bulk_load(points);
+    BuiltIndex {
+        point_count,
+        seed,
+        working_crs,
+        lonlat: lonlat.into(),
+        tree,
+        build_time: start.elapsed(),
+    }
+}
+
+impl Algorithm for SpatialIndex {
+    fn name(&self) -> String {
+        "Spatial Index (R-tree)".to_string()
+    }
+
+    fn calculate_and_box_output(&mut self, scene: &InputScene) -> Option<AlgorithmOutput> {
+        let Some(probe) = scene
+            .secondary_geometry()
+            .and_then(|input| input.geometry.coords_iter().next())
+        else {
+            return Some(AlgorithmOutput::text(
+                "The second geometry has no probe point.",
+            ));
+        };
+        let working_crs = scene.working_crs;
+        let (Some(projected_probe), Some(units_per_meter)) = (
+            working_crs.forward(probe),
+            working_crs.units_per_meter(probe),
+        ) else {
+            return Some(AlgorithmOutput::text(format!(
+                "The probe is outside {}.",
+                working_crs
+            )));
+        };
+        let (k, radius_meters) = (self.k, self.radius_meters);
+        let radius = radius_meters * units_per_meter;
+        let index = self.index(working_crs);
+        let query_point = [projected_probe.x, projected_probe.y];
+
+        let start = Instant::now();
+        let nearest: Vec<usize> = index
+            .tree
+            .nearest_neighbor_iter(&query_point)
+            .take(k)
+            .map(|point| point.data)
+            .collect();
+        let nearest_time = start.elapsed();
+
+        let start = Instant::now();
+        // rstar compares squared distances.
+        let within: Vec<usize> = index
+            .tree
+            .locate_within_distance(query_point, radius * radius)
+            .map(|point| point.data)
+            .collect();
+        let within_time = start.elapsed();
+
+        let text = format!("{} points indexed", index.tree.size());
+        let table = OutputTable {
+            headers: ["Operation", "Results", "Time"].map(String::from).to_vec(),
+            rows: vec![
+                vec![
+                    "Project and bulk load".to_string(),
+                    index.tree.size().to_string(),
+                    format_duration(index.build_time),
+                ],
+                vec![
+                    format!("{} nearest", k),
+                    nearest.len().to_string(),
+                    format_duration(nearest_time),
+                ],
+                vec![
+                    format!("Within {:.0} m", radius_meters),
+                    within.len().to_string(),
+                    format_duration(within_time),
+                ],
+            ],
+        };
+
+        let lonlat_of = |indices: &[usize]| -> Vec<Coord<f64>> {
+            indices.iter().map(|i| index.lonlat[*i]).collect()
+        };
+        let mut overlays = vec![
+            Overlay::point_cloud(index.lonlat.clone(), POINT_COLOR),
+            Overlay::point_cloud(lonlat_of(&within), WITHIN_COLOR),
+        ];
+        if let Some(circle) = from_working_crs(&circle(projected_probe, radius), &working_crs) {
+            overlays.push(Overlay::dashed_ring(circle.0, WITHIN_COLOR));
+        }
+        overlays.extend(
+            lonlat_of(&nearest)
+                .into_iter()
+                .map(|point| Overlay::connector(probe, point, NEAREST_COLOR)),
+        );
+        overlays.push(Overlay::point_cloud(lonlat_of(&nearest), NEAREST_COLOR));
+        Some(
+            AlgorithmOutput::text(text)
+                .with_overlays(overlays)
+                .with_table(table),
+        )
+    }
+
+    fn has_doc_example(&self) -> bool {
+        true
+    }
+
+    fn doc_example(&self, _scene: &InputScene) -> Result<String, String> {
+        Err(format!(
+            "No snippet: the {} indexed points are generated at random and too many to write \
+             out, and the index comes from rstar rather than geo. To reproduce the queries, \
+             project the points into the working CRS, load them with `RTree::bulk_load`, then \
+             call `nearest_neighbor_iter(&probe).take({})` and \
+             `locate_within_distance(probe, radius * radius)`.",
+            self.point_count, self.k
+        ))
+    }
+
+    fn settings_ui(&mut self, ui: &mut Ui) {
+        ui.add(
+            egui::Slider::new(&mut self.point_count, 100..=100_000)
+                .logarithmic(true)
+                .text("Points"),
+        );
+        ui.add(egui::DragValue::new(&mut self.seed).prefix("Seed: "));
+        ui.add(egui::Slider::new(&mut self.k, 1..=100).text("k"));
+        ui.add(egui::Slider::new(&mut self.radius_meters, 0.0..=100_000.0).text("Radius (m)"));
+    }
+}
+
+/// A closed ring approximating a circle, in the working CRS.
+fn circle(center: Coord<f64>, radius: f64) -> LineString<f64> {
+    const SEGMENTS: usize = 64;
+    (0..=SEGMENTS)
+        .map(|i| {
+            let angle = std::f64::consts::TAU * i as f64 / SEGMENTS as f64;
+            Coord {
+                x: center.x + radius * angle.cos(),
+                y: center.y + radius * angle.sin(),
+            }
+        })
+        .collect()
+}
+
+fn format_duration(duration: Duration) -> String {
+    format!("{:.1} µs", duration.as_secs_f64() * 1e6)
+}
@@ -0,0 +1,278 @@
+//! Triangulations of the draggable points and of polygon inputs, and the Voronoi diagram dual to
+//! the Delaunay triangulation. Dragging a point shows edges flipping as the triangulation updates.
+
+use std::collections::HashMap;
+
+use super::{
+    Algorithm, AlgorithmOutput, DocExample, InputScene, active_in_working_crs, binding_name,
+    from_working_crs, to_working_crs,
+};
+use crate::overlay::Overlay;
+use egui::Color32;
+use geo::{
+    BoundingRect as _, Coord, Geometry, Line, MultiPoint, Rect, Triangle, TriangulateDelaunay,
+    TriangulateEarcut,
+};
+
+const DELAUNAY_COLOR: Color32 = Color32::from_rgb(70, 130, 180);
+const CONSTRAINED_COLOR: Color32 = Color32::from_rgb(154, 205, 50);
+const EARCUT_COLOR: Color32 = Color32::from_rgb(255, 140, 0);
+const VORONOI_COLOR: Color32 = Color32::from_rgb(138, 43, 226);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriangulationMethod {
+    /// Delaunay triangulation of every draggable point.
+    Delaunay,
+    /// Delaunay triangulation of the active polygon with its rings as constraint edges, keeping
+    /// only the triangles inside it.
+    Constrained,
+    /// Ear clipping of the active polygon.
+    Earcut,
+}
+
+pub struct Triangulation {
+    method: TriangulationMethod,
+}
+
+impl Triangulation {
+    pub fn new(method: TriangulationMethod) -> Self {
+        Triangulation { method }
+    }
+
+    fn color(&self) -> Color32 {
+        match self.method {
+            TriangulationMethod::Delaunay => DELAUNAY_COLOR,
+            TriangulationMethod::Constrained => CONSTRAINED_COLOR,
+            TriangulationMethod::Earcut => EARCUT_COLOR,
+        }
+    }
+
+    // The triangulated input in the working CRS and its triangles, shared by the output and the
+    // snippet. The input is all the draggable points for Delaunay and the active polygon otherwise.
+    fn triangulate(
+        &self,
+        scene: &InputScene,
+    ) -> Result<(Geometry<f64>, Vec<Triangle<f64>>), String> {
+        if self.method == TriangulationMethod::Delaunay {
+            let (points, triangles) = delaunay_triangles(scene)?;
+            return Ok((points.into(), triangles));
+        }
+        let Geometry::Polygon(polygon) = active_in_working_crs(scene)? else {
+            return Err("Requires a polygon input.".to_string());
+        };
+        let triangles = match self.method {
+            TriangulationMethod::Constrained => polygon
+                .constrained_triangulation(Default::default())
+                .map_err(|e| format!("Triangulation failed: {e:?}"))?,
+            _ => polygon.earcut_triangles(),
+        };
+        Ok((polygon.into(), triangles))
+    }
+}
+
+impl Algorithm for Triangulation {
+    fn name(&self) -> String {
+        match self.method {
+            TriangulationMethod::Delaunay => "Delaunay Triangulation",
+            TriangulationMethod::Constrained => "Constrained Triangulation",
+            TriangulationMethod::Earcut => "Earcut Triangulation",
+        }
+        .to_string()
+    }
+
+    fn calculate_and_box_output(&mut self, scene: &InputScene) -> Option<AlgorithmOutput> {
+        let triangles = match self.triangulate(scene) {
+            Ok((_, triangles)) => triangles,
+            Err(message) => return Some(AlgorithmOutput::text(message)),
+        };
+
+        let text = format!("{} triangles", triangles.len());
+        let edges: Vec<Line<f64>> = triangles.iter().flat_map(|t| t.to_lines()).collect();
+        Some(AlgorithmOutput::text(text).with_overlays(segment_overlays(
+            &edges,
+            scene,
+            self.color(),
+        )))
+    }
+
+    fn has_doc_example(&self) -> bool {
+        true
+    }
+
+    fn doc_example(&self, scene: &InputScene) -> Result<String, String> {
+        let (input, triangles) = self.triangulate(scene)?;
+        let example = DocExample::default()
+            .in_working_crs(&scene.working_crs)
+            .geometry(binding_name(&input), input);
+        let (example, call) = match self.method {
+            TriangulationMethod::Delaunay => (
+                example.import("TriangulateDelaunay"),
+                "points.unconstrained_triangulation().unwrap()",
+            ),
+            TriangulationMethod::Constrained => (
+                example.import("TriangulateDelaunay"),
+                "polygon\n    .constrained_triangulation(Default::default())\n    .unwrap()",
+            ),
+            TriangulationMethod::Earcut => (
+                example.import("TriangulateEarcut"),
+                "polygon.earcut_triangles()",
+            ),
+        };
+        Ok(example
+            .line(format!("let triangles = {};", call))
+            .assert_eq("triangles.len()", triangles.len())
+            .render())
+    }
+}
+
+/// The Voronoi diagram of the draggable points, built from the circumcenters of the Delaunay
+/// triangles. Cells on the convex hull are unbounded, so their outer edges are drawn as rays that
+/// run well past the points.
+pub struct Voronoi;
+
+impl Algorithm for Voronoi {
+    fn name(&self) -> String {
+        "Voronoi Diagram".to_string()
+    }
+
+    fn calculate_and_box_output(&mut self, scene: &InputScene) -> Option<AlgorithmOutput> {
+        let triangles = match delaunay_triangles(scene) {
+            Ok((_, triangles)) => triangles,
+            Err(message) => return Some(AlgorithmOutput::text(message)),
+        };
+        let Some(bounds) = triangles
+            .iter()
+            .flat_map(|triangle| triangle.to_array())
+            .collect::<MultiPoint<f64>>()
+            .bounding_rect()
+        else {
+            return Some(AlgorithmOutput::text("The Voronoi diagram is empty."));
+        };
+
+        let edges = voronoi_edges(&triangles, &bounds);
+        let text = format!("{} edges", edges.len());
+        Some(AlgorithmOutput::text(text).with_overlays(segment_overlays(
+            &edges,
+            scene,
+            VORONOI_COLOR,
+        )))
+    }
+
+    fn has_doc_example(&self) -> bool {
+        true
+    }
+
+    /// `geo` has no Voronoi diagram, so the snippet stops at the Delaunay triangulation it is
+    /// built from.
+    fn doc_example(&self, scene: &InputScene) -> Result<String, String> {
+        let (points, triangles) = delaunay_triangles(scene)?;
+        Ok(DocExample::default()
+            .import("TriangulateDelaunay")
+            .in_working_crs(&scene.working_crs)
+            .geometry("points", points)
+            .line("let triangles = points.unconstrained_triangulation().unwrap();")
+            .assert_eq("triangles.len()", triangles.len())
+            .line("// geo has no Voronoi diagram. Its vertices are the circumcenters of these")
+            .line("// triangles: join the circumcenters of triangles that share an edge, and")
+            .line("// cast a ray outwards from triangles with an edge on the convex hull.")
+            .render())
+    }
+}
+
+// All the draggable points in the working CRS and their Delaunay triangles.
+fn delaunay_triangles(scene: &InputScene) -> Result<(MultiPoint<f64>, Vec<Triangle<f64>>), String> {
+    let points = scene.all_vertices();
+    if points.0.len() < 3 {
+        return Err("Requires at least three points.".to_string());
+    }
+    let points = to_working_crs(&points, &scene.working_crs)
+        .ok_or_else(|| format!("Points are outside {}.", scene.working_crs))?;
+    let triangles = points
+        .unconstrained_triangulation()
+        .map_err(|e| format!("Triangulation failed: {e:?}"))?;
+    Ok((points, triangles))
+}
+
+/// Joins the circumcenters of triangles that share an edge. An edge used by a single triangle lies
+/// on the convex hull, and gets a ray pointing away from the triangle.
+fn voronoi_edges(triangles: &[Triangle<f64>], bounds: &Rect<f64>) -> Vec<Line<f64>> {
+    let ray_length = bounds.width().hypot(bounds.height());
+    let key = |coord: Coord<f64>| (coord.x.to_bits(), coord.y.to_bits());
+    let edge_key = |a: Coord<f64>, b: Coord<f64>| {
+        let (a, b) = (key(a), key(b));
+        if a < b { (a, b) } else { (b, a) }
+    };
+
+    let mut shared: HashMap<_, Vec<(usize, Line<f64>)>> = HashMap::new();
+    for (index, triangle) in triangles.iter().enumerate() {
+        for edge in triangle.to_lines() {
+            shared
+                .entry(edge_key(edge.start, edge.end))
+                .or_default()
+                .push((index, edge));
+        }
+    }
+
+    let centers: Vec<Option<Coord<f64>>> = triangles.iter().map(circumcenter).collect();
+    let mut edges = Vec::new();
+    for neighbours in shared.values() {
+        match neighbours.as_slice() {
+            [(a, _), (b, _)] => {
+                if let (Some(start), Some(end)) = (centers[*a], centers[*b]) {
+                    edges.push(Line::new(start, end));
+                }
+            }
+            [(index, edge)] => {
+                let Some(center) = centers[*index] else {
+                    continue;
+                };
+                // The outward normal of a hull edge points away from the opposite vertex.
+                let opposite = triangles[*index].to_array().into_iter().find(|vertex| {
+                    key(*vertex) != key(edge.start) && key(*vertex) != key(edge.end)
+                });
+                let Some(opposite) = opposite else {
+                    continue;
+                };
+                let direction = edge.delta();
+                let mut normal = Coord {
+                    x: direction.y,
+                    y: -direction.x,
+                };
+                let midpoint = (edge.start + edge.end) / 2.0;
+                let to_opposite = opposite - midpoint;
+                if normal.x * to_opposite.x + normal.y * to_opposite.y > 0.0 {
+                    normal = -normal;
+                }
+                let length = normal.x.hypot(normal.y);
+                if length > 0.0 {
+                    edges.push(Line::new(center, center + normal * (ray_length / length)));
+                }
+            }
+            _ => {}
+        }
+    }
+    edges
+}
+
+fn circumcenter(triangle: &Triangle<f64>) -> Option<Coord<f64>> {
+    let [a, b, c] = triangle.to_array();
+    let (b, c) = (b - a, c - a);
+    let d = 2.0 * (b.x * c.y - b.y * c.x);
+    if d == 0.0 {
+        return None;
+    }
+    let b_len = b.x * b.x + b.y * b.y;
+    let c_len = c.x * c.x + c.y * c.y;
+    Some(Coord {
+        x: a.x + (c.y * b_len - b.y * c_len) / d,
+        y: a.y + (b.x * c_len - c.x * b_len) / d,
+    })
+}
+
+fn segment_overlays(edges: &[Line<f64>], scene: &InputScene, color: Color32) -> Vec<Overlay> {
+    let edges = edges
+        .iter()
+        .filter_map(|edge| from_working_crs(edge, &scene.working_crs))
+        .collect();
+    vec![Overlay::segments(edges, color)]
+}
@@ -0,0 +1,233 @@
+//! Validity checks on every input, with each problem marked where it happens. Dragging a polygon
+//! vertex across an opposite edge makes a bowtie, which explains surprising areas and boolean
+//! results elsewhere in the window.
+
+use super::{Algorithm, AlgorithmOutput, DocExample, InputScene, OutputTable, binding_name};
+use crate::overlay::Overlay;
+use egui::Color32;
+use geo::algorithm::validation::{InvalidLineString, InvalidPolygon, RingRole};
+use geo::line_intersection::{LineIntersection, line_intersection};
+use geo::{Contains, Coord, CoordsIter, Geometry, Line, LineString, Polygon, Validation as _};
+
+const PROBLEM_COLOR: Color32 = Color32::from_rgb(220, 20, 60);
+
+/// A `geo` validation error with the place it happens, for the message, the map marker and the
+/// table row, which all share its number.
+struct LocatedProblem {
+    geometry: String,
+    problem: String,
+    /// `None` for errors without a finite position, e.g. a non-finite coordinate.
+    position: Option<Coord<f64>>,
+}
+
+/// Runs `geo`'s validation on every input and marks each error on the map. Most errors only
+/// name the offending ring, so the marker goes on the first place in that ring where the error
+/// shows.
+pub struct Validation;
+
+impl Algorithm for Validation {
+    fn name(&self) -> String {
+        "Validation".to_string()
+    }
+
+    fn calculate_and_box_output(&mut self, scene: &InputScene) -> Option<AlgorithmOutput> {
+        let problems: Vec<LocatedProblem> = scene
+            .geometries
+            .iter()
+            .flat_map(|input| {
+                locate_problems(&input.geometry)
+                    .into_iter()
+                    .map(move |(problem, position)| LocatedProblem {
+                        geometry: input.name.clone(),
+                        problem,
+                        position,
+                    })
+            })
+            .collect();
+        if problems.is_empty() {
+            return Some(AlgorithmOutput::text("All inputs are valid."));
+        }
+
+        let messages: Vec<String> = problems
+            .iter()
+            .enumerate()
+            .map(|(i, problem)| format!("#{} {}: {}", i + 1, problem.geometry, problem.problem))
+            .collect();
+        let text = format!("{} problems\n{}", problems.len(), messages.join("\n"));
+        let overlays = problems
+            .iter()
+            .enumerate()
+            .filter_map(|(i, problem)| {
+                let position = problem.position?;
+                Some(Overlay::labeled_marker(
+                    position,
+                    format!("#{}", i + 1),
+                    PROBLEM_COLOR,
+                ))
+            })
+            .collect();
+        let coordinate = |value: Option<f64>| match value {
+            Some(value) => format!("{:.6}", value),
+            None => "—".to_string(),
+        };
+        let table = OutputTable {
+            headers: ["#", "Geometry", "Problem", "Longitude", "Latitude"]
+                .map(String::from)
+                .to_vec(),
+            rows: problems
+                .iter()
+                .enumerate()
+                .map(|(i, problem)| {
+                    vec![
+                        format!("{}", i + 1),
+                        problem.geometry.clone(),
+                        problem.problem.clone(),
+                        coordinate(problem.position.map(|position| position.x)),
+                        coordinate(problem.position.map(|position| position.y)),
+                    ]
+                })
+                .collect(),
+        };
+        Some(
+            AlgorithmOutput::text(text)
+                .with_overlays(overlays)
+                .with_table(table),
+        )
+    }
+
+    fn has_doc_example(&self) -> bool {
+        true
+    }
+
+    /// Validates the active input only, since a snippet reproduces one call.
+    fn doc_example(&self, scene: &InputScene) -> Result<String, String> {
+        let geometry = &scene
+            .active_geometry()
+            .ok_or_else(|| "No input geometry is selected.".to_string())?
+            .geometry;
+        // The same problems the table lists for this input.
+        let problems = locate_problems(geometry);
+        let name = binding_name(geometry);
+        let example = DocExample::default()
+            .import("Validation")
+            .geometry(name, geometry.clone());
+        let example = if problems.is_empty() {
+            example.line(format!("assert!({}.is_valid());", name))
+        } else {
+            example.line(format!("assert!(!{}.is_valid());", name))
+        };
+        Ok(example
+            .assert_eq(
+                &format!("{}.validation_errors().len()", name),
+                problems.len(),
+            )
+            .render())
+    }
+}
+
+/// Each of `geo`'s validation errors for `geometry`, with the position to mark it at.
+fn locate_problems(geometry: &Geometry<f64>) -> Vec<(String, Option<Coord<f64>>)> {
+    match geometry {
+        Geometry::LineString(line) => line
+            .validation_errors()
+            .into_iter()
+            .map(|error| {
+                let position = match error {
+                    InvalidLineString::TooFewPoints => line.0.first().copied(),
+                    InvalidLineString::NonFiniteCoord(_) => None,
+                };
+                (error.to_string(), position)
+            })
+            .collect(),
+        Geometry::Polygon(polygon) => {
+            let rings: Vec<&LineString<f64>> = std::iter::once(polygon.exterior())
+                .chain(polygon.interiors())
+                .collect();
+            let intersections = ring_intersections(&rings);
+            let ring_index = |role: &RingRole| match role {
+                RingRole::Exterior => 0,
+                RingRole::Interior(index) => index + 1,
+            };
+            let crossing = |a: usize, b: usize| {
+                intersections
+                    .iter()
+                    .find(|(ring_a, ring_b, _)| {
+                        (*ring_a, *ring_b) == (a, b) || (*ring_a, *ring_b) == (b, a)
+                    })
+                    .map(|(_, _, position)| *position)
+            };
+            let shell = Polygon::new(polygon.exterior().clone(), vec![]);
+            polygon
+                .validation_errors()
+                .into_iter()
+                .map(|error| {
+                    let position = match &error {
+                        InvalidPolygon::TooFewPointsInRing(role) => rings
+                            .get(ring_index(role))
+                            .and_then(|ring| ring.0.first().copied()),
+                        InvalidPolygon::SelfIntersection(role) => {
+                            let ring = ring_index(role);
+                            crossing(ring, ring)
+                        }
+                        InvalidPolygon::NonFiniteCoord(..) => None,
+                        InvalidPolygon::InteriorRingNotContainedInExteriorRing(role) => {
+                            rings.get(ring_index(role)).and_then(|hole| {
+                                hole.0
+                                    .iter()
+                                    .find(|coord| !shell.contains(*coord))
+                                    .or(hole.0.first())
+                                    .copied()
+                            })
+                        }
+                        InvalidPolygon::IntersectingRingsOnALine(a, b)
+                        | InvalidPolygon::IntersectingRingsOnAnArea(a, b) => {
+                            crossing(ring_index(a), ring_index(b))
+                        }
+                    };
+                    (error.to_string(), position)
+                })
+                .collect()
+        }
+        // Other inputs are marked at their first vertex.
+        _ => geometry
+            .validation_errors()
+            .into_iter()
+            .map(|error| (error.to_string(), geometry.coords_iter().next()))
+            .collect(),
+    }
+}
+
+/// Points where two segments of the rings cross or touch, with the indices of both rings, apart
+/// from the shared vertex of consecutive segments in the same ring.
+fn ring_intersections(rings: &[&LineString<f64>]) -> Vec<(usize, usize, Coord<f64>)> {
+    let segments: Vec<(usize, usize, Line<f64>)> = rings
+        .iter()
+        .enumerate()
+        .flat_map(|(ring, line)| {
+            line.lines()
+                .enumerate()
+                .map(move |(index, segment)| (ring, index, segment))
+        })
+        .collect();
+    let segment_count = |ring: usize| rings[ring].0.len().saturating_sub(1);
+
+    let mut positions = Vec::new();
+    for (i, (ring_a, index_a, a)) in segments.iter().enumerate() {
+        for (ring_b, index_b, b) in &segments[i + 1..] {
+            let adjacent = ring_a == ring_b
+                && (index_b - index_a == 1
+                    || (*index_a == 0 && *index_b + 1 == segment_count(*ring_b)));
+            match line_intersection(*a, *b) {
+                Some(LineIntersection::SinglePoint {
+                    intersection,
+                    is_proper,
+                }) if is_proper || !adjacent => positions.push((*ring_a, *ring_b, intersection)),
+                Some(LineIntersection::Collinear { intersection }) => {
+                    positions.push((*ring_a, *ring_b, intersection.start))
+                }
+                _ => {}
+            }
+        }
+    }
+    positions
+}
@@ -0,0 +1,329 @@
+//! Longitude wrapping around the ±180° meridian.
+//!
+//! Input vertices are stored with longitudes in `-180.0..180.0`, so a ring that crosses the
+//! antimeridian has a jump of more than 180° between two neighbouring vertices. Algorithms get the
+//! ring with continuous longitudes from [`unwrap_longitudes`] instead, which may go past ±180° but
+//! has no jump, and the map gets it cut at the antimeridian by [`split_at_antimeridian`].
+
+use galileo_types::cartesian::Point2;
+use galileo_types::geo::impls::GeoPoint2d;
+use galileo_types::geo::{Crs, GeoPoint, NewGeoPoint};
+use geo::Coord;
+
+use crate::error::DragError;
+use crate::{project_geo_point_to_cartesian, unproject_cartesian_point_to_geo};
+
+/// Latitude at which web mercator's square world ends. Points dragged further are clamped to it.
+pub const MAX_MERCATOR_LATITUDE: f64 = 85.051_128_779_806_59;
+
+/// `lon` shifted by a multiple of 360° into `-180.0..180.0`.
+pub fn wrap_longitude(lon: f64) -> f64 {
+    (lon + 180.0).rem_euclid(360.0) - 180.0
+}
+
+/// Wraps the longitude of a map CRS position and clamps its latitude to what `crs` can show, so
+/// dragging past the edge of the world or towards a pole keeps the vertex on the map.
+pub fn normalize_map_position(position: &Point2, crs: &Crs) -> Result<Point2, DragError> {
+    let geo_point = unproject_cartesian_point_to_geo(position, crs)?;
+    let max_lat = if *crs == Crs::EPSG3857 {
+        MAX_MERCATOR_LATITUDE
+    } else {
+        90.0
+    };
+    let lon = wrap_longitude(geo_point.lon());
+    let lat = geo_point.lat().clamp(-max_lat, max_lat);
+    if lon == geo_point.lon() && lat == geo_point.lat() {
+        return Ok(*position);
+    }
+    project_geo_point_to_cartesian(&GeoPoint2d::lonlat(lon, lat), crs)
+}
+
+/// Whether two consecutive coordinates are on opposite sides of the antimeridian, i.e. more than
+/// 180° of longitude apart.
+pub fn crosses_antimeridian(coords: &[Coord<f64>]) -> bool {
+    coords
+        .windows(2)
+        .any(|pair| (pair[1].x - pair[0].x).abs() > 180.0)
+}
+
+/// Shifts longitudes by multiples of 360° so that no two consecutive coordinates are more than
+/// 180° apart. The first coordinate is kept as is.
+pub fn unwrap_longitudes(coords: &[Coord<f64>]) -> Vec<Coord<f64>> {
+    let mut unwrapped: Vec<Coord<f64>> = Vec::with_capacity(coords.len());
+    for coord in coords {
+        let lon = match unwrapped.last() {
+            Some(previous) => previous.x + wrap_longitude(coord.x - previous.x),
+            None => coord.x,
+        };
+        unwrapped.push(Coord { x: lon, y: coord.y });
+    }
+    unwrapped
+}
+
+/// Cuts a line into pieces with longitudes in `-180.0..=180.0`, adding a vertex on the
+/// antimeridian wherever the line crosses it. A line that does not cross comes back as one piece.
+/// A vertex exactly on the antimeridian is not repeated, and a line that only touches it does not
+/// leave a single-point piece behind.
+pub fn split_at_antimeridian(coords: &[Coord<f64>]) -> Vec<Vec<Coord<f64>>> {
+    let unwrapped = unwrap_longitudes(coords);
+    let mut pieces = Vec::new();
+    let mut piece: Vec<Coord<f64>> = Vec::new();
+    let push = |piece: &mut Vec<Coord<f64>>, coord: Coord<f64>| {
+        if piece.last() != Some(&coord) {
+            piece.push(coord);
+        }
+    };
+    // Number of 360° turns the current piece is offset by.
+    let turn = |lon: f64| ((lon + 180.0) / 360.0).floor();
+    for (i, coord) in unwrapped.iter().enumerate() {
+        if let Some(previous) = i.checked_sub(1).map(|i| unwrapped[i]) {
+            let (from, to) = (turn(previous.x), turn(coord.x));
+            if from != to {
+                // The meridian between the two turns, e.g. 180° going east from turn 0 to 1.
+                let meridian = from.max(to) * 360.0 - 180.0;
+                let t = (meridian - previous.x) / (coord.x - previous.x);
+                let lat = previous.y + t * (coord.y - previous.y);
+                push(
+                    &mut piece,
+                    Coord {
+                        x: meridian - from * 360.0,
+                        y: lat,
+                    },
+                );
+                if piece.len() > 1 {
+                    pieces.push(std::mem::take(&mut piece));
+                }
+                piece = vec![Coord {
+                    x: meridian - to * 360.0,
+                    y: lat,
+                }];
+            }
+        }
+        push(
+            &mut piece,
+            Coord {
+                x: coord.x - turn(coord.x) * 360.0,
+                y: coord.y,
+            },
+        );
+    }
+    if piece.len() > 1 || (pieces.is_empty() && !piece.is_empty()) {
+        pieces.push(piece);
+    }
+    pieces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use galileo_types::cartesian::CartesianPoint2d;
+
+    fn coords(lonlat: &[(f64, f64)]) -> Vec<Coord<f64>> {
+        lonlat.iter().map(|&(x, y)| Coord { x, y }).collect()
+    }
+
+    fn assert_coords_close(actual: &[Coord<f64>], expected: &[Coord<f64>]) {
+        assert_eq!(
+            actual.len(),
+            expected.len(),
+            "{:?} vs {:?}",
+            actual,
+            expected
+        );
+        for (actual_coord, expected_coord) in actual.iter().zip(expected) {
+            assert!(
+                (actual_coord.x - expected_coord.x).abs() < 1e-9
+                    && (actual_coord.y - expected_coord.y).abs() < 1e-9,
+                "{:?} vs {:?}",
+                actual,
+                expected
+            );
+        }
+    }
+
+    fn assert_pieces(actual: Vec<Vec<Coord<f64>>>, expected: &[&[(f64, f64)]]) {
+        assert_eq!(actual.len(), expected.len(), "{:?}", actual);
+        for (piece, expected_piece) in actual.iter().zip(expected) {
+            assert_coords_close(piece, &coords(expected_piece));
+        }
+    }
+
+    fn project(lon: f64, lat: f64) -> Point2 {
+        project_geo_point_to_cartesian(&GeoPoint2d::lonlat(lon, lat), &Crs::EPSG3857).unwrap()
+    }
+
+    #[test]
+    fn wrap_longitude_maps_into_half_open_range() {
+        for (lon, wrapped) in [
+            (0.0, 0.0),
+            (179.5, 179.5),
+            (180.0, -180.0),
+            (-180.0, -180.0),
+            (190.0, -170.0),
+            (-190.0, 170.0),
+            (540.0, -180.0),
+            (-725.0, -5.0),
+        ] {
+            assert!(
+                (wrap_longitude(lon) - wrapped).abs() < 1e-9,
+                "wrap_longitude({}) = {}",
+                lon,
+                wrap_longitude(lon)
+            );
+        }
+    }
+
+    #[test]
+    fn normalize_map_position_wraps_and_clamps() {
+        let crs = Crs::EPSG3857;
+        let inside = project(10.0, 20.0);
+        assert_eq!(normalize_map_position(&inside, &crs).unwrap(), inside);
+
+        let wrapped = normalize_map_position(&project(190.0, 20.0), &crs).unwrap();
+        let expected = project(-170.0, 20.0);
+        assert!(
+            (wrapped.x() - expected.x()).abs() < 1e-3 && (wrapped.y() - expected.y()).abs() < 1e-3
+        );
+
+        let clamped = normalize_map_position(&project(10.0, 89.0), &crs).unwrap();
+        let expected = project(10.0, MAX_MERCATOR_LATITUDE);
+        assert!(
+            (clamped.x() - expected.x()).abs() < 1e-3 && (clamped.y() - expected.y()).abs() < 1e-3
+        );
+    }
+
+    #[test]
+    fn crosses_antimeridian_needs_a_jump_over_180_degrees() {
+        assert!(crosses_antimeridian(&coords(&[
+            (170.0, 0.0),
+            (-170.0, 0.0)
+        ])));
+        assert!(crosses_antimeridian(&coords(&[
+            (0.0, 0.0),
+            (10.0, 0.0),
+            (-175.0, 0.0),
+            (175.0, 0.0),
+        ])));
+        assert!(!crosses_antimeridian(&coords(&[(-90.0, 0.0), (90.0, 0.0)])));
+        assert!(!crosses_antimeridian(&coords(&[(179.0, 0.0), (0.0, 0.0)])));
+        assert!(!crosses_antimeridian(&coords(&[(170.0, 0.0)])));
+    }
+
+    #[test]
+    fn unwrap_longitudes_removes_jumps() {
+        assert_coords_close(
+            &unwrap_longitudes(&coords(&[(170.0, 0.0), (-170.0, 1.0), (-160.0, 2.0)])),
+            &coords(&[(170.0, 0.0), (190.0, 1.0), (200.0, 2.0)]),
+        );
+        // Going around the world twice keeps counting up.
+        assert_coords_close(
+            &unwrap_longitudes(&coords(&[
+                (0.0, 0.0),
+                (120.0, 0.0),
+                (-120.0, 0.0),
+                (0.0, 0.0),
+                (120.0, 0.0),
+            ])),
+            &coords(&[
+                (0.0, 0.0),
+                (120.0, 0.0),
+                (240.0, 0.0),
+                (360.0, 0.0),
+                (480.0, 0.0),
+            ]),
+        );
+    }
+
+    #[test]
+    fn split_without_crossing_is_one_piece() {
+        assert_pieces(
+            split_at_antimeridian(&coords(&[(10.0, 0.0), (20.0, 5.0)])),
+            &[&[(10.0, 0.0), (20.0, 5.0)]],
+        );
+        assert_pieces(
+            split_at_antimeridian(&coords(&[(10.0, 0.0)])),
+            &[&[(10.0, 0.0)]],
+        );
+    }
+
+    #[test]
+    fn split_interpolates_the_crossing() {
+        assert_pieces(
+            split_at_antimeridian(&coords(&[(170.0, 0.0), (-170.0, 10.0)])),
+            &[
+                &[(170.0, 0.0), (180.0, 5.0)],
+                &[(-180.0, 5.0), (-170.0, 10.0)],
+            ],
+        );
+        assert_pieces(
+            split_at_antimeridian(&coords(&[(-170.0, 10.0), (170.0, 0.0)])),
+            &[
+                &[(-170.0, 10.0), (-180.0, 5.0)],
+                &[(180.0, 5.0), (170.0, 0.0)],
+            ],
+        );
+    }
+
+    #[test]
+    fn split_at_vertex_on_the_antimeridian() {
+        assert_pieces(
+            split_at_antimeridian(&coords(&[(170.0, 0.0), (-180.0, 0.0), (-170.0, 0.0)])),
+            &[
+                &[(170.0, 0.0), (180.0, 0.0)],
+                &[(-180.0, 0.0), (-170.0, 0.0)],
+            ],
+        );
+        assert_pieces(
+            split_at_antimeridian(&coords(&[(-170.0, 0.0), (-180.0, 0.0), (170.0, 0.0)])),
+            &[
+                &[(-170.0, 0.0), (-180.0, 0.0)],
+                &[(180.0, 0.0), (170.0, 0.0)],
+            ],
+        );
+        // Touching the antimeridian and turning back leaves no single-point piece.
+        assert_pieces(
+            split_at_antimeridian(&coords(&[(170.0, 0.0), (-180.0, 0.0), (170.0, 10.0)])),
+            &[
+                &[(170.0, 0.0), (180.0, 0.0)],
+                &[(180.0, 0.0), (170.0, 10.0)],
+            ],
+        );
+    }
+
+    #[test]
+    fn split_repeated_crossings() {
+        assert_pieces(
+            split_at_antimeridian(&coords(&[(170.0, 0.0), (-170.0, 0.0), (170.0, 0.0)])),
+            &[
+                &[(170.0, 0.0), (180.0, 0.0)],
+                &[(-180.0, 0.0), (-170.0, 0.0), (-180.0, 0.0)],
+                &[(180.0, 0.0), (170.0, 0.0)],
+            ],
+        );
+    }
+
+    #[test]
+    fn split_multiple_wraps() {
+        assert_pieces(
+            split_at_antimeridian(&coords(&[
+                (0.0, 0.0),
+                (120.0, 0.0),
+                (-120.0, 0.0),
+                (0.0, 0.0),
+                (120.0, 0.0),
+                (-120.0, 0.0),
+            ])),
+            &[
+                &[(0.0, 0.0), (120.0, 0.0), (180.0, 0.0)],
+                &[
+                    (-180.0, 0.0),
+                    (-120.0, 0.0),
+                    (0.0, 0.0),
+                    (120.0, 0.0),
+                    (180.0, 0.0),
+                ],
+                &[(-180.0, 0.0), (-120.0, 0.0)],
+            ],
+        );
+    }
+}
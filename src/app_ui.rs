@@ -6,13 +6,34 @@ use galileo_types::geo::impls::GeoPoint2d;
 // For now, only direct dependencies for the struct and its impls are included.
 
 // Import Algorithm and AlgorithmOutput
-use crate::algorithms::{Algorithm, HaversineDistance};
-use galileo_types::impls::Contour; // For Contour type
-use geo::Coord; // For Coord type
-use std::fmt::Display; // Needed for Box<dyn Display ...>
+use crate::algorithms::{
+    AffineKind, AffineTransformAlgorithm, Algorithm, AlgorithmOutput, AreaComparison, Bearing,
+    BooleanOp, BooleanOperation, BoundingRect, Buffer, Centroid, ChaikinSmoothing, ClosestPoint,
+    ConcaveHull, ConvexHull, Densify, DensifyMetric, Destination, EuclideanDistance, Extremes,
+    FrechetDistance, HausdorffDistance, HaversineDistance, InputScene, InteriorPoint,
+    LineInterpolatePoint, LineLocatePoint, Midpoint, MinimumRotatedRect, NamedGeometry,
+    OffsetCurve, Orient, PredicateMatrix, Segmentize, SimplifyLine, SimplifyMethod, SpatialIndex,
+    TraceStep, TransformOrigin, Triangulation, TriangulationMethod, Validation, Voronoi, Winding,
+};
+use crate::antimeridian;
+use crate::crs::WorkingCrs;
+use crate::error::{DragError, LogEntry};
+use crate::generate::{GeneratorKind, GeneratorSettings, generate};
+use crate::gizmo::GizmoLayout;
+use crate::input::{InputGeometry, InputKind, VertexRef};
+use crate::overlay::{self, Overlay, ScreenTransform};
+use crate::snapping::{GridUnit, SnapKind};
+use crate::{
+    PointConfig, SharedEditState, add_input, move_point, project_geo_point_to_cartesian,
+    remove_last_input, unproject_cartesian_point_to_geo,
+};
+use galileo::layer::FeatureId;
+use galileo_types::cartesian::CartesianPoint2d;
+use galileo_types::geo::NewGeoPoint;
+use geo::{LineString, Winding as _};
 
-// Type alias for the stored, type-erased output of algorithms.
-pub type StoredAlgorithmOutput = Box<dyn Display + Send + Sync>;
+// Type alias for the stored output of algorithms.
+pub type StoredAlgorithmOutput = AlgorithmOutput;
 
 pub struct EguiMapApp {
     pub map: EguiMapState,
@@ -20,10 +41,50 @@ pub struct EguiMapApp {
     pub resolution: f64,
     algorithms: Vec<Box<dyn Algorithm>>,
     algorithm_outputs: Vec<Option<StoredAlgorithmOutput>>,
+    /// Whether each algorithm's overlays are drawn on the map.
+    overlay_visible: Vec<bool>,
+    /// Index of the input geometry that single-geometry algorithms run on.
+    active_geometry: usize,
+    /// Index of the second input for algorithms that compare two geometries.
+    secondary_geometry: usize,
+    edit_state: SharedEditState,
+    /// Arrow key nudge distance in degrees. Holding shift nudges ten times as far.
+    nudge_step: f64,
+    /// Planar CRS that algorithms run Euclidean computations in.
+    working_crs: WorkingCrs,
+    /// Most recent error and the `ctx.input(|i| i.time)` at which its toast disappears.
+    toast: Option<(LogEntry, f64)>,
+    /// Whether building the algorithm input failed on the previous frame, so a persistent failure
+    /// is only logged once.
+    input_scene_failed: bool,
+    generator: GeneratorSettings,
+    /// Number of inputs from the app config. Inputs past these were generated and can be removed.
+    configured_input_count: usize,
+    /// The step-through timeline of each algorithm, while it is open.
+    timelines: Vec<Option<TraceTimeline>>,
 }
 
+/// The recorded steps of an algorithm and the one whose overlays are drawn.
+struct TraceTimeline {
+    steps: Result<Vec<TraceStep>, String>,
+    position: usize,
+}
+
+impl TraceTimeline {
+    fn current(&self) -> Option<&TraceStep> {
+        self.steps.as_ref().ok()?.get(self.position)
+    }
+}
+
+/// How long an error toast stays on screen, in seconds.
+const TOAST_DURATION: f64 = 5.0;
+
 impl EguiMapApp {
-    pub fn new(map_state: EguiMapState) -> Self {
+    pub fn new(
+        map_state: EguiMapState,
+        edit_state: SharedEditState,
+        working_crs: WorkingCrs,
+    ) -> Self {
         let position = map_state
             .map()
             .view()
@@ -32,72 +93,907 @@ impl EguiMapApp {
         let resolution = map_state.map().view().resolution();
 
         // Initialize algorithms
-        let algorithms: Vec<Box<dyn Algorithm>> = vec![Box::new(HaversineDistance)];
+        let algorithms: Vec<Box<dyn Algorithm>> = vec![
+            Box::new(HaversineDistance),
+            Box::new(EuclideanDistance),
+            Box::new(Bearing),
+            Box::new(Destination::default()),
+            Box::new(Midpoint),
+            Box::new(AreaComparison),
+            Box::new(Centroid),
+            Box::new(InteriorPoint),
+            Box::new(BoundingRect),
+            Box::new(MinimumRotatedRect),
+            Box::new(Extremes),
+            Box::new(ConvexHull),
+            Box::new(ConcaveHull::default()),
+            Box::new(SimplifyLine::new(SimplifyMethod::Rdp)),
+            Box::new(SimplifyLine::new(SimplifyMethod::Vw)),
+            Box::new(SimplifyLine::new(SimplifyMethod::VwPreserve)),
+            Box::new(Buffer::default()),
+            Box::new(OffsetCurve::default()),
+            Box::new(BooleanOp::new(BooleanOperation::Intersection)),
+            Box::new(BooleanOp::new(BooleanOperation::Union)),
+            Box::new(BooleanOp::new(BooleanOperation::Difference)),
+            Box::new(BooleanOp::new(BooleanOperation::ReverseDifference)),
+            Box::new(BooleanOp::new(BooleanOperation::Xor)),
+            Box::new(PredicateMatrix),
+            Box::new(Validation),
+            Box::new(Winding),
+            Box::new(Orient::default()),
+            Box::new(ClosestPoint),
+            Box::new(LineLocatePoint),
+            Box::new(LineInterpolatePoint::default()),
+            Box::new(HausdorffDistance),
+            Box::new(FrechetDistance),
+            Box::new(Densify::new(DensifyMetric::Euclidean)),
+            Box::new(Densify::new(DensifyMetric::Haversine)),
+            Box::new(ChaikinSmoothing::default()),
+            Box::new(Segmentize::default()),
+            Box::new(SpatialIndex::default()),
+            Box::new(Triangulation::new(TriangulationMethod::Delaunay)),
+            Box::new(Triangulation::new(TriangulationMethod::Constrained)),
+            Box::new(Triangulation::new(TriangulationMethod::Earcut)),
+            Box::new(Voronoi),
+            Box::new(AffineTransformAlgorithm::new(
+                AffineKind::Rotate,
+                edit_state.affine_params.clone(),
+            )),
+            Box::new(AffineTransformAlgorithm::new(
+                AffineKind::Scale,
+                edit_state.affine_params.clone(),
+            )),
+            Box::new(AffineTransformAlgorithm::new(
+                AffineKind::Translate,
+                edit_state.affine_params.clone(),
+            )),
+            Box::new(AffineTransformAlgorithm::new(
+                AffineKind::Skew,
+                edit_state.affine_params.clone(),
+            )),
+            Box::new(AffineTransformAlgorithm::new(
+                AffineKind::Combined,
+                edit_state.affine_params.clone(),
+            )),
+        ];
         let mut algorithm_outputs: Vec<Option<StoredAlgorithmOutput>> =
             Vec::with_capacity(algorithms.len());
         for _ in 0..algorithms.len() {
             algorithm_outputs.push(None);
         }
 
+        let configured_input_count = edit_state.inputs.read().unwrap().len();
         Self {
             map: map_state,
             position,
             resolution,
             algorithms,
+            overlay_visible: vec![true; algorithm_outputs.len()],
+            timelines: std::iter::repeat_with(|| None)
+                .take(algorithm_outputs.len())
+                .collect(),
             algorithm_outputs,
+            active_geometry: 0,
+            secondary_geometry: 1,
+            edit_state,
+            nudge_step: 0.001,
+            working_crs,
+            toast: None,
+            input_scene_failed: false,
+            generator: GeneratorSettings::default(),
+            configured_input_count,
         }
     }
 
-    // Helper to get line geometry. Assumes a single line feature in a specific layer type.
-    fn get_line_geometry(&self) -> Option<Contour<Coord<f64>>> {
-        let map_ref = self.map.map();
-        for layer_trait_object in map_ref.layers().iter() {
-            if let Some(feature_layer) = layer_trait_object
-                .as_any()
-                .downcast_ref::<galileo::layer::FeatureLayer<
-                    geo::Coord<f64>,
-                    Contour<Coord<f64>>,
-                    galileo::symbol::SimpleContourSymbol,
-                    galileo_types::geometry_type::GeoSpace2d,
-                >>()
+    fn working_crs_ui(&mut self, ui: &mut egui::Ui) {
+        let center = (self.position.lon(), self.position.lat());
+        let options = [
+            WorkingCrs::Wgs84,
+            WorkingCrs::WebMercator,
+            WorkingCrs::utm_for(center.0, center.1),
+            WorkingCrs::LocalEqualArea {
+                center_lon: center.0,
+                center_lat: center.1,
+            },
+        ];
+        ui.horizontal(|ui| {
+            ui.label("Working CRS");
+            egui::ComboBox::from_id_salt("working_crs")
+                .selected_text(self.working_crs.to_string())
+                .show_ui(ui, |ui| {
+                    for option in options {
+                        ui.selectable_value(&mut self.working_crs, option, option.to_string());
+                    }
+                });
+        });
+        if let WorkingCrs::Utm { zone, north } = &mut self.working_crs {
+            ui.horizontal(|ui| {
+                ui.label("Zone");
+                ui.add(egui::DragValue::new(zone).range(1..=60));
+                ui.checkbox(north, "North");
+            });
+        }
+    }
+
+    fn snapping_ui(&self, ui: &mut egui::Ui) {
+        let mut settings = self.edit_state.snap_settings.write().unwrap();
+        ui.collapsing("Snapping", |ui| {
+            ui.checkbox(&mut settings.to_vertex, "Snap to vertex");
+            ui.checkbox(&mut settings.to_segment, "Snap to segment");
+            ui.checkbox(&mut settings.to_integer, "Snap to integer coordinates");
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut settings.to_grid, "Snap to grid");
+                ui.add(
+                    egui::DragValue::new(&mut settings.grid_size)
+                        .speed(0.01)
+                        .range(0.0..=f64::MAX),
+                );
+                egui::ComboBox::from_id_salt("snap_grid_unit")
+                    .selected_text(match settings.grid_unit {
+                        GridUnit::Degrees => "degrees",
+                        GridUnit::MapUnits => "map units",
+                    })
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut settings.grid_unit, GridUnit::Degrees, "degrees");
+                        ui.selectable_value(
+                            &mut settings.grid_unit,
+                            GridUnit::MapUnits,
+                            "map units",
+                        );
+                    });
+            });
+            ui.add(
+                egui::Slider::new(&mut settings.tolerance_px, 1.0..=30.0).text("Tolerance (px)"),
+            );
+        });
+    }
+
+    fn active_geometry_ui(&mut self, ui: &mut egui::Ui) {
+        let names: Vec<String> = self
+            .edit_state
+            .inputs
+            .read()
+            .unwrap()
+            .iter()
+            .map(|input| input.name.clone())
+            .collect();
+        geometry_selector(ui, "Active geometry", &names, &mut self.active_geometry);
+        self.orientation_ui(ui);
+        geometry_selector(ui, "Second geometry", &names, &mut self.secondary_geometry);
+    }
+
+    fn orientation_ui(&mut self, ui: &mut egui::Ui) {
+        let kind = self
+            .edit_state
+            .inputs
+            .read()
+            .unwrap()
+            .get(self.active_geometry)
+            .map(|input| input.kind);
+        ui.horizontal(|ui| {
+            let reorientable = matches!(kind, Some(InputKind::LineString | InputKind::Polygon));
+            if ui
+                .add_enabled(reorientable, egui::Button::new("Reverse"))
+                .clicked()
             {
-                if let Some((_id, feature)) = feature_layer.features().iter().next() {
-                    return Some(feature.clone());
+                self.reorient_active_geometry(|_, _| true);
+            }
+            if ui
+                .add_enabled(
+                    kind == Some(InputKind::Polygon),
+                    egui::Button::new("Normalize orientation"),
+                )
+                .on_hover_text("Exterior counter-clockwise, holes clockwise")
+                .clicked()
+            {
+                // The map CRS keeps the orientation of lon/lat, so the rings can be tested as is.
+                self.reorient_active_geometry(|ring, is_ccw| (ring == 0) != is_ccw);
+            }
+        });
+    }
+
+    // Reverses the rings of the active input for which `should_reverse(ring index, is_ccw)` holds,
+    // by moving every vertex to the position of its mirror in the ring.
+    fn reorient_active_geometry(&mut self, should_reverse: impl Fn(usize, bool) -> bool) {
+        let Some(input) = self
+            .edit_state
+            .inputs
+            .read()
+            .unwrap()
+            .get(self.active_geometry)
+            .cloned()
+        else {
+            return;
+        };
+        for (ring, points) in input.rings.iter().enumerate() {
+            let mut ring_line: LineString<f64> = points
+                .iter()
+                .map(|p| geo::coord! { x: p.x(), y: p.y() })
+                .collect();
+            ring_line.close();
+            let is_ccw = ring_line.is_ccw();
+            if !should_reverse(ring, is_ccw) {
+                continue;
+            }
+            for (vertex, position) in points.iter().rev().enumerate() {
+                let vertex = VertexRef {
+                    geometry: self.active_geometry,
+                    ring,
+                    vertex,
+                };
+                let result = self
+                    .edit_state
+                    .vertex_feature_id(vertex)
+                    .ok_or(DragError::SelectedFeatureIdMissing)
+                    .and_then(|feature_id| {
+                        move_point(self.map.map_mut(), &self.edit_state, feature_id, *position)
+                    });
+                if let Err(e) = result {
+                    self.edit_state.error_log.push("reorienting a ring", e);
+                    return;
                 }
             }
         }
-        None
     }
+
+    fn generator_ui(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("Generate input").show(ui, |ui| {
+            let settings = &mut self.generator;
+            egui::ComboBox::from_id_salt("generator_kind")
+                .selected_text(settings.kind.to_string())
+                .show_ui(ui, |ui| {
+                    for kind in GeneratorKind::ALL {
+                        ui.selectable_value(&mut settings.kind, kind, kind.to_string());
+                    }
+                });
+            ui.add(egui::Slider::new(&mut settings.vertex_count, 3..=200).text("Vertices"));
+            ui.add(egui::DragValue::new(&mut settings.seed).prefix("Seed: "));
+            ui.add(
+                egui::Slider::new(&mut settings.radius_meters, 1_000.0..=200_000.0)
+                    .logarithmic(true)
+                    .text("Radius (m)"),
+            );
+
+            let input_count = self.edit_state.inputs.read().unwrap().len();
+            ui.horizontal(|ui| {
+                if ui.button("Add at map center").clicked() {
+                    self.add_generated_input();
+                }
+                if ui
+                    .add_enabled(
+                        input_count > self.configured_input_count,
+                        egui::Button::new("Remove last generated"),
+                    )
+                    .clicked()
+                {
+                    if let Err(e) = remove_last_input(self.map.map_mut(), &self.edit_state) {
+                        self.edit_state.error_log.push("removing an input", e);
+                    }
+                    let last = input_count.saturating_sub(2);
+                    self.active_geometry = self.active_geometry.min(last);
+                    self.secondary_geometry = self.secondary_geometry.min(last);
+                }
+            });
+        });
+    }
+
+    // Generates an input around the map center and makes it the active geometry.
+    fn add_generated_input(&mut self) {
+        let center = PointConfig::new(self.position.lon(), self.position.lat());
+        let config = generate(&self.generator, center);
+        let result = InputGeometry::from_config(&config, &self.edit_state.map_crs)
+            .and_then(|input| add_input(self.map.map_mut(), &self.edit_state, input));
+        match result {
+            Ok(index) => self.active_geometry = index,
+            Err(e) => self
+                .edit_state
+                .error_log
+                .push("adding a generated input", e),
+        }
+        // A new seed each time, so pressing the button again gives a different geometry.
+        self.generator.seed += 1;
+    }
+
+    // Returns the lon/lat of the selected point, if there is one.
+    fn selected_point_lonlat(&self) -> Option<(FeatureId, VertexRef, GeoPoint2d)> {
+        let (feature_id, vertex) = self.edit_state.selected_vertex()?;
+        let point = self.edit_state.vertex_position(vertex)?;
+        let geo_point = unproject_cartesian_point_to_geo(&point, &self.edit_state.map_crs).ok()?;
+        Some((feature_id, vertex, geo_point))
+    }
+
+    fn move_point_to_lonlat(&mut self, feature_id: FeatureId, lon: f64, lat: f64) {
+        let result =
+            project_geo_point_to_cartesian(&GeoPoint2d::lonlat(lon, lat), &self.edit_state.map_crs)
+                .and_then(|position| {
+                    move_point(self.map.map_mut(), &self.edit_state, feature_id, position)
+                });
+        if let Err(e) = result {
+            self.edit_state.error_log.push("moving a point", e);
+        }
+    }
+
+    // Moves the selected point with the arrow keys, unless a text field has keyboard focus.
+    fn apply_keyboard_nudge(&mut self, ctx: &egui::Context) {
+        if ctx.wants_keyboard_input() {
+            return;
+        }
+        let (dx, dy, fast) = ctx.input(|input| {
+            let mut dx = 0.0;
+            let mut dy = 0.0;
+            if input.key_pressed(egui::Key::ArrowLeft) {
+                dx -= 1.0;
+            }
+            if input.key_pressed(egui::Key::ArrowRight) {
+                dx += 1.0;
+            }
+            if input.key_pressed(egui::Key::ArrowDown) {
+                dy -= 1.0;
+            }
+            if input.key_pressed(egui::Key::ArrowUp) {
+                dy += 1.0;
+            }
+            (dx, dy, input.modifiers.shift)
+        });
+        if dx == 0.0 && dy == 0.0 {
+            return;
+        }
+        let Some((feature_id, _, geo_point)) = self.selected_point_lonlat() else {
+            return;
+        };
+        let step = if fast {
+            self.nudge_step * 10.0
+        } else {
+            self.nudge_step
+        };
+        self.move_point_to_lonlat(
+            feature_id,
+            geo_point.lon() + dx * step,
+            geo_point.lat() + dy * step,
+        );
+    }
+
+    fn selected_point_ui(&mut self, ui: &mut egui::Ui) {
+        ui.collapsing("Selected vertex", |ui| {
+            let Some((feature_id, vertex, geo_point)) = self.selected_point_lonlat() else {
+                ui.label("Click or drag a point to select it.");
+                return;
+            };
+            let mut lon = geo_point.lon();
+            let mut lat = geo_point.lat();
+            let mut changed = false;
+
+            let name = self.edit_state.inputs.read().unwrap()[vertex.geometry]
+                .name
+                .clone();
+            ui.label(format!(
+                "{}, ring {}, vertex {}",
+                name, vertex.ring, vertex.vertex
+            ));
+            ui.horizontal(|ui| {
+                ui.label("Lon");
+                changed |= ui
+                    .add(
+                        egui::DragValue::new(&mut lon)
+                            .speed(0.0001)
+                            .max_decimals(8)
+                            .range(-180.0..=180.0),
+                    )
+                    .changed();
+                ui.label("Lat");
+                changed |= ui
+                    .add(
+                        egui::DragValue::new(&mut lat)
+                            .speed(0.0001)
+                            .max_decimals(8)
+                            .range(-85.0511..=85.0511),
+                    )
+                    .changed();
+            });
+            ui.horizontal(|ui| {
+                ui.label("Arrow key step (°)");
+                ui.add(
+                    egui::DragValue::new(&mut self.nudge_step)
+                        .speed(0.0001)
+                        .max_decimals(8)
+                        .range(0.0..=10.0),
+                );
+            });
+
+            if changed {
+                self.move_point_to_lonlat(feature_id, lon, lat);
+            }
+        });
+    }
+
+    // Lists every input vertex with its WGS84 and map CRS coordinates. Clicking a row selects the
+    // vertex.
+    fn vertex_table_ui(&self, ui: &mut egui::Ui) {
+        ui.collapsing("Vertices", |ui| {
+            let selected_vertex = self.edit_state.selected_vertex().map(|(_, vertex)| vertex);
+            let inputs = self.edit_state.inputs.read().unwrap().clone();
+            let mut clicked_vertex = None;
+
+            egui::Grid::new("vertex_table")
+                .striped(true)
+                .num_columns(7)
+                .show(ui, |ui| {
+                    for header in ["Geometry", "Ring", "Vertex", "Lon", "Lat", "Map X", "Map Y"] {
+                        ui.strong(header);
+                    }
+                    ui.end_row();
+
+                    for (geometry_index, geometry) in inputs.iter().enumerate() {
+                        for (ring_index, ring) in geometry.rings.iter().enumerate() {
+                            for (vertex_index, point) in ring.iter().enumerate() {
+                                let vertex = VertexRef {
+                                    geometry: geometry_index,
+                                    ring: ring_index,
+                                    vertex: vertex_index,
+                                };
+                                let geo_point = unproject_cartesian_point_to_geo(
+                                    point,
+                                    &self.edit_state.map_crs,
+                                )
+                                .ok();
+                                if ui
+                                    .selectable_label(
+                                        selected_vertex == Some(vertex),
+                                        &geometry.name,
+                                    )
+                                    .clicked()
+                                {
+                                    clicked_vertex = Some(vertex);
+                                }
+                                ui.label(ring_index.to_string());
+                                ui.label(vertex_index.to_string());
+                                ui.monospace(
+                                    geo_point
+                                        .map_or("N/A".to_string(), |p| format!("{:.6}", p.lon())),
+                                );
+                                ui.monospace(
+                                    geo_point
+                                        .map_or("N/A".to_string(), |p| format!("{:.6}", p.lat())),
+                                );
+                                ui.monospace(format!("{:.2}", point.x()));
+                                ui.monospace(format!("{:.2}", point.y()));
+                                ui.end_row();
+                            }
+                        }
+                    }
+                });
+
+            if let Some(vertex) = clicked_vertex {
+                self.edit_state.select_vertex(vertex);
+            }
+        });
+    }
+
+    // Draws the overlays of every algorithm whose overlays are switched on.
+    fn paint_algorithm_overlays(&self, ui: &egui::Ui, map_rect: egui::Rect) {
+        let view = self.map.map().view();
+        let Some(transform) = ScreenTransform::new(map_rect, ui.ctx().pixels_per_point(), |p| {
+            view.screen_to_map(p)
+        }) else {
+            return;
+        };
+        let painter = ui.painter_at(map_rect);
+
+        for ((output, visible), timeline) in self
+            .algorithm_outputs
+            .iter()
+            .zip(&self.overlay_visible)
+            .zip(&self.timelines)
+        {
+            if !*visible {
+                continue;
+            }
+            // A timeline that is open draws its current step in place of the output.
+            let overlays = match (timeline.as_ref().and_then(TraceTimeline::current), output) {
+                (Some(step), _) => &step.overlays,
+                (None, Some(output)) => &output.overlays,
+                (None, None) => continue,
+            };
+            for overlay in overlays {
+                overlay::paint_overlay(&painter, &transform, &self.edit_state.map_crs, overlay);
+            }
+        }
+    }
+
+    // Names of the line and polygon inputs with a ring that crosses the antimeridian.
+    fn inputs_crossing_antimeridian(&self) -> Vec<String> {
+        let map_crs = &self.edit_state.map_crs;
+        self.edit_state
+            .inputs
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|input| input.has_contours())
+            .filter(|input| {
+                (0..input.rings.len()).any(|ring| {
+                    input.ring_to_lonlat(ring, map_crs).is_ok_and(|mut coords| {
+                        if input.rings_are_closed() {
+                            coords.extend(coords.first().copied());
+                        }
+                        antimeridian::crosses_antimeridian(&coords)
+                    })
+                })
+            })
+            .map(|input| input.name.clone())
+            .collect()
+    }
+
+    // Draws the antimeridian while an input crosses it, since the input's lines are cut there.
+    fn paint_dateline_indicator(&self, ui: &egui::Ui, map_rect: egui::Rect, crossing: &[String]) {
+        if crossing.is_empty() {
+            return;
+        }
+        let view = self.map.map().view();
+        let Some(transform) = ScreenTransform::new(map_rect, ui.ctx().pixels_per_point(), |p| {
+            view.screen_to_map(p)
+        }) else {
+            return;
+        };
+        let painter = ui.painter_at(map_rect);
+        let color = egui::Color32::from_rgb(255, 69, 0);
+        let max_lat = antimeridian::MAX_MERCATOR_LATITUDE;
+        for lon in [-180.0, 180.0] {
+            let dateline = Overlay::connector(
+                geo::coord! { x: lon, y: -max_lat },
+                geo::coord! { x: lon, y: max_lat },
+                color,
+            );
+            overlay::paint_overlay(&painter, &transform, &self.edit_state.map_crs, &dateline);
+        }
+    }
+
+    // Draws a ring around the selected point and a marker at the active snap target.
+    fn paint_edit_indicators(&self, ui: &egui::Ui, map_rect: egui::Rect) {
+        let view = self.map.map().view();
+        let Some(transform) = ScreenTransform::new(map_rect, ui.ctx().pixels_per_point(), |p| {
+            view.screen_to_map(p)
+        }) else {
+            return;
+        };
+        let painter = ui.painter_at(map_rect);
+
+        if let Some((_, vertex)) = self.edit_state.selected_vertex() {
+            if let Some(point) = self.edit_state.vertex_position(vertex) {
+                painter.circle_stroke(
+                    transform.map_to_screen(&point),
+                    9.0,
+                    egui::Stroke::new(2.0, egui::Color32::YELLOW),
+                );
+            }
+        }
+
+        let Some(snap) = *self.edit_state.snap_indicator.read().unwrap() else {
+            return;
+        };
+        let color = match snap.kind {
+            SnapKind::Vertex(_) => egui::Color32::from_rgb(255, 0, 255),
+            SnapKind::Segment(_, _) => egui::Color32::from_rgb(255, 140, 0),
+            SnapKind::Grid | SnapKind::Integer => egui::Color32::from_rgb(0, 200, 255),
+        };
+        overlay::paint_square_marker(
+            &painter,
+            transform.map_to_screen(&snap.position),
+            9.0,
+            color,
+        );
+    }
+
+    // Publishes the transform origin in the map CRS so the event handler can hit-test the gizmo.
+    fn update_gizmo_origin(&self, scene: Option<&InputScene>) {
+        let params = self.edit_state.affine_params.read().unwrap();
+        let origin = scene
+            .filter(|_| params.show_gizmo)
+            .and_then(|scene| {
+                let origin = params.origin_in_working_crs(scene)?;
+                scene.working_crs.inverse(origin)
+            })
+            .and_then(|lonlat| {
+                project_geo_point_to_cartesian(
+                    &GeoPoint2d::lonlat(lonlat.x, lonlat.y),
+                    &self.edit_state.map_crs,
+                )
+                .ok()
+            });
+        *self.edit_state.gizmo_origin.write().unwrap() = origin;
+    }
+
+    // Draws the transform origin with its rotation and scale handles.
+    fn paint_transform_gizmo(&self, ui: &egui::Ui, map_rect: egui::Rect) {
+        let Some(origin) = *self.edit_state.gizmo_origin.read().unwrap() else {
+            return;
+        };
+        let view = self.map.map().view();
+        let Some(transform) = ScreenTransform::new(map_rect, ui.ctx().pixels_per_point(), |p| {
+            view.screen_to_map(p)
+        }) else {
+            return;
+        };
+        let painter = ui.painter_at(map_rect);
+        let layout = GizmoLayout::new(
+            origin,
+            &self.edit_state.affine_params.read().unwrap(),
+            view.resolution(),
+        );
+
+        let origin = transform.map_to_screen(&layout.origin);
+        let rotate = transform.map_to_screen(&layout.rotate);
+        let scale = transform.map_to_screen(&layout.scale);
+        let rotate_color = egui::Color32::from_rgb(65, 105, 225);
+        let scale_color = egui::Color32::from_rgb(46, 139, 87);
+        painter.line_segment([origin, rotate], egui::Stroke::new(1.5, rotate_color));
+        painter.line_segment([origin, scale], egui::Stroke::new(1.5, scale_color));
+        painter.circle_filled(rotate, 6.0, rotate_color);
+        overlay::paint_square_marker(&painter, scale, 5.0, scale_color);
+        overlay::paint_square_marker(&painter, origin, 6.0, egui::Color32::BLACK);
+    }
+
+    fn transform_gizmo_ui(&self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("Transform gizmo").show(ui, |ui| {
+            let mut params = self.edit_state.affine_params.write().unwrap();
+            ui.checkbox(&mut params.show_gizmo, "Show gizmo");
+            match params.origin {
+                TransformOrigin::Centroid => {
+                    ui.label("Origin: centroid of the active geometry");
+                }
+                TransformOrigin::Custom(lonlat) => {
+                    ui.label(format!("Origin: {:.6}, {:.6}", lonlat.x, lonlat.y));
+                    if ui.button("Reset origin to centroid").clicked() {
+                        params.origin = TransformOrigin::Centroid;
+                    }
+                }
+            }
+        });
+    }
+
+    // Converts the current inputs to WGS84 `geo` geometries for the algorithms.
+    fn input_scene(&self) -> Result<InputScene, DragError> {
+        let geometries = self
+            .edit_state
+            .inputs
+            .read()
+            .unwrap()
+            .iter()
+            .map(|input| {
+                Ok(NamedGeometry {
+                    name: input.name.clone(),
+                    geometry: input.to_geo(&self.edit_state.map_crs)?,
+                })
+            })
+            .collect::<Result<Vec<_>, DragError>>()?;
+        Ok(InputScene {
+            geometries,
+            active: self.active_geometry,
+            secondary: self.secondary_geometry,
+            working_crs: self.working_crs,
+        })
+    }
+
+    fn error_log_ui(&self, ui: &mut egui::Ui) {
+        let entries = self.edit_state.error_log.entries();
+        ui.collapsing(format!("Log ({})", entries.len()), |ui| {
+            if ui.button("Clear").clicked() {
+                self.edit_state.error_log.clear();
+            }
+            egui::ScrollArea::vertical()
+                .max_height(150.0)
+                .stick_to_bottom(true)
+                .show(ui, |ui| {
+                    for entry in &entries {
+                        ui.colored_label(ui.visuals().error_fg_color, &entry.message);
+                    }
+                });
+        });
+    }
+
+    // Shows the most recent error at the bottom of the screen for a few seconds.
+    fn show_error_toast(&mut self, ctx: &egui::Context) {
+        let now = ctx.input(|input| input.time);
+        if let Some(latest) = self.edit_state.error_log.latest() {
+            let is_new = self
+                .toast
+                .as_ref()
+                .is_none_or(|(shown, _)| shown.sequence != latest.sequence);
+            if is_new {
+                self.toast = Some((latest, now + TOAST_DURATION));
+            }
+        }
+
+        let Some((entry, expires_at)) = &self.toast else {
+            return;
+        };
+        if now >= *expires_at {
+            return;
+        }
+        egui::Area::new(egui::Id::new("error_toast"))
+            .anchor(egui::Align2::CENTER_BOTTOM, egui::vec2(0.0, -20.0))
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.colored_label(ui.visuals().error_fg_color, &entry.message);
+                });
+            });
+        ctx.request_repaint_after(std::time::Duration::from_secs_f64(expires_at - now));
+    }
+}
+
+// A combo box picking one of the input geometries by name.
+fn geometry_selector(ui: &mut egui::Ui, label: &str, names: &[String], selected: &mut usize) {
+    ui.horizontal(|ui| {
+        ui.label(label);
+        egui::ComboBox::from_id_salt(label)
+            .selected_text(names.get(*selected).map_or("None", String::as_str))
+            .show_ui(ui, |ui| {
+                for (index, name) in names.iter().enumerate() {
+                    ui.selectable_value(selected, index, name);
+                }
+            });
+    });
+}
+
+// A "Step through" toggle for an algorithm's trace and, while it is on, a slider over the steps.
+fn trace_timeline_ui(ui: &mut egui::Ui, timeline: &mut Option<TraceTimeline>) {
+    let mut stepping = timeline.is_some();
+    if ui.checkbox(&mut stepping, "Step through").changed() {
+        *timeline = stepping.then(|| TraceTimeline {
+            steps: Ok(Vec::new()),
+            position: 0,
+        });
+    }
+    let Some(TraceTimeline { steps, position }) = timeline else {
+        return;
+    };
+    let steps = match steps {
+        Ok(steps) if !steps.is_empty() => steps,
+        Ok(_) => {
+            ui.label("No steps recorded.");
+            return;
+        }
+        Err(message) => {
+            ui.label(message.as_str());
+            return;
+        }
+    };
+    let last = steps.len() - 1;
+    *position = (*position).min(last);
+    ui.horizontal(|ui| {
+        if ui
+            .add_enabled(*position > 0, egui::Button::new("◀"))
+            .clicked()
+        {
+            *position -= 1;
+        }
+        ui.add(egui::Slider::new(&mut *position, 0..=last).text(format!("of {}", last)));
+        if ui
+            .add_enabled(*position < last, egui::Button::new("▶"))
+            .clicked()
+        {
+            *position += 1;
+        }
+    });
+    ui.label(&steps[*position].description);
+}
+
+// A collapsible panel with the algorithm's current computation as a `geo` snippet, and a button
+// that copies it. The snippet is only generated while the panel is open.
+fn doc_example_ui(ui: &mut egui::Ui, algorithm: &dyn Algorithm, scene: Option<&InputScene>) {
+    egui::CollapsingHeader::new("Rust example")
+        .id_salt(("doc_example", algorithm.name()))
+        .show(ui, |ui| {
+            let example = scene
+                .ok_or_else(|| "The input geometries could not be read.".to_string())
+                .and_then(|scene| algorithm.doc_example(scene));
+            match example {
+                Ok(code) => {
+                    if ui.button("📋 Copy").clicked() {
+                        ui.ctx().copy_text(code.clone());
+                    }
+                    ui.add(
+                        egui::TextEdit::multiline(&mut code.as_str())
+                            .code_editor()
+                            .desired_width(f32::INFINITY),
+                    );
+                }
+                Err(message) => {
+                    ui.label(message);
+                }
+            }
+        });
 }
 
 impl eframe::App for EguiMapApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        let line_contour = self.get_line_geometry();
+        self.apply_keyboard_nudge(ctx);
+
+        let scene = match self.input_scene() {
+            Ok(scene) => {
+                self.input_scene_failed = false;
+                Some(scene)
+            }
+            Err(e) => {
+                if !self.input_scene_failed {
+                    self.edit_state
+                        .error_log
+                        .push("reading the input geometries", e);
+                    self.input_scene_failed = true;
+                }
+                None
+            }
+        };
 
-        if let Some(ref geom_contour) = line_contour {
-            for (i, algorithm) in self.algorithms.iter().enumerate() {
-                self.algorithm_outputs[i] = algorithm.calculate_and_box_output(geom_contour);
+        self.update_gizmo_origin(scene.as_ref());
+        if let Some(ref scene) = scene {
+            for (i, algorithm) in self.algorithms.iter_mut().enumerate() {
+                self.algorithm_outputs[i] = algorithm.calculate_and_box_output(scene);
+                if let Some(timeline) = self.timelines[i].as_mut() {
+                    timeline.steps = algorithm.trace(scene);
+                }
             }
         } else {
             for output in self.algorithm_outputs.iter_mut() {
                 *output = None;
             }
+            for timeline in self.timelines.iter_mut().flatten() {
+                timeline.steps = Ok(Vec::new());
+            }
         }
 
         egui::CentralPanel::default().show(ctx, |ui| {
+            let map_rect = ui.available_rect_before_wrap();
             EguiMap::new(&mut self.map)
                 .with_position(&mut self.position)
                 .with_resolution(&mut self.resolution)
                 .show_ui(ui);
+            self.paint_algorithm_overlays(ui, map_rect);
+            self.paint_edit_indicators(ui, map_rect);
+            let crossing = self.inputs_crossing_antimeridian();
+            self.paint_dateline_indicator(ui, map_rect, &crossing);
+            self.paint_transform_gizmo(ui, map_rect);
 
             egui::Window::new("Galileo map").show(ctx, |ui| {
+                self.vertex_table_ui(ui);
+                self.selected_point_ui(ui);
+                self.snapping_ui(ui);
+                ui.separator();
+                self.working_crs_ui(ui);
+                self.active_geometry_ui(ui);
+                if !crossing.is_empty() {
+                    ui.colored_label(
+                        egui::Color32::from_rgb(255, 69, 0),
+                        format!("Crosses the antimeridian: {}", crossing.join(", ")),
+                    );
+                }
+                self.transform_gizmo_ui(ui);
+                self.generator_ui(ui);
+
                 // Display algorithm outputs
                 ui.label("Algorithm Outputs:");
-                for (i, algorithm) in self.algorithms.iter().enumerate() {
+                for (i, algorithm) in self.algorithms.iter_mut().enumerate() {
+                    algorithm.settings_ui(ui);
                     let output_opt_ref = &self.algorithm_outputs[i];
                     algorithm.display_ui(ui, output_opt_ref);
+                    if output_opt_ref
+                        .as_ref()
+                        .is_some_and(|output| !output.overlays.is_empty())
+                    {
+                        ui.checkbox(&mut self.overlay_visible[i], "Show on map");
+                    }
+                    if algorithm.has_trace() {
+                        trace_timeline_ui(ui, &mut self.timelines[i]);
+                    }
+                    if algorithm.has_doc_example() {
+                        doc_example_ui(ui, &**algorithm, scene.as_ref());
+                    }
                 }
+
+                ui.separator();
+                self.error_log_ui(ui);
             });
         });
+
+        self.show_error_toast(ctx);
     }
 }
@@ -0,0 +1,470 @@
+//! Planar "working" coordinate reference systems that algorithms project their WGS84 input into.
+//!
+//! Euclidean `geo` algorithms (area, buffer, simplify, ...) interpret coordinates as planar, so
+//! running them on raw degrees gives results in meaningless units. The working CRS makes that
+//! choice explicit. Projections use the WGS84 ellipsoid and follow Snyder, "Map Projections: A
+//! Working Manual" (USGS Professional Paper 1395).
+
+use std::f64::consts::FRAC_PI_2;
+use std::fmt;
+
+use geo::{Coord, Distance, Haversine, Point};
+
+const WGS84_A: f64 = 6_378_137.0;
+const WGS84_F: f64 = 1.0 / 298.257_223_563;
+const UTM_K0: f64 = 0.9996;
+const UTM_FALSE_EASTING: f64 = 500_000.0;
+const UTM_FALSE_NORTHING_SOUTH: f64 = 10_000_000.0;
+/// Latitude limit of the spherical web mercator projection.
+const WEB_MERCATOR_MAX_LAT: f64 = 85.051_128_779_806_6;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WorkingCrs {
+    /// Longitude/latitude degrees, i.e. no projection at all.
+    Wgs84,
+    /// EPSG:3857. Conformal, but distances and areas are inflated by `1 / cos(lat)²` away from the
+    /// equator.
+    WebMercator,
+    /// Universal Transverse Mercator. `zone` is 1..=60.
+    Utm { zone: u8, north: bool },
+    /// Lambert azimuthal equal-area projection centered at the given point, so areas are exact
+    /// and distances are accurate close to the center.
+    LocalEqualArea { center_lon: f64, center_lat: f64 },
+}
+
+impl WorkingCrs {
+    /// The UTM zone containing `lon`/`lat`. Ignores the Norway and Svalbard exceptions.
+    pub fn utm_for(lon: f64, lat: f64) -> Self {
+        let zone = (((lon + 180.0) / 6.0).floor() as i64).rem_euclid(60) as u8 + 1;
+        WorkingCrs::Utm {
+            zone,
+            north: lat >= 0.0,
+        }
+    }
+
+    /// Whether coordinates in this CRS are in meters rather than degrees.
+    pub fn is_metric(&self) -> bool {
+        !matches!(self, WorkingCrs::Wgs84)
+    }
+
+    pub fn unit_suffix(&self) -> &'static str {
+        if self.is_metric() { "m" } else { "°" }
+    }
+
+    /// Projects a WGS84 lon/lat coordinate into this CRS.
+    pub fn forward(&self, lonlat: Coord<f64>) -> Option<Coord<f64>> {
+        let projected = match *self {
+            WorkingCrs::Wgs84 => lonlat,
+            WorkingCrs::WebMercator => web_mercator_forward(lonlat)?,
+            WorkingCrs::Utm { zone, north } => utm_forward(lonlat, zone, north),
+            WorkingCrs::LocalEqualArea {
+                center_lon,
+                center_lat,
+            } => laea_forward(lonlat, center_lon, center_lat)?,
+        };
+        (projected.x.is_finite() && projected.y.is_finite()).then_some(projected)
+    }
+
+    /// Unprojects a coordinate in this CRS back to WGS84 lon/lat.
+    pub fn inverse(&self, projected: Coord<f64>) -> Option<Coord<f64>> {
+        let lonlat = match *self {
+            WorkingCrs::Wgs84 => projected,
+            WorkingCrs::WebMercator => web_mercator_inverse(projected),
+            WorkingCrs::Utm { zone, north } => utm_inverse(projected, zone, north),
+            WorkingCrs::LocalEqualArea {
+                center_lon,
+                center_lat,
+            } => laea_inverse(projected, center_lon, center_lat)?,
+        };
+        (lonlat.x.is_finite() && lonlat.y.is_finite()).then_some(lonlat)
+    }
+
+    /// Length of one meter on the ground near `lonlat`, in this CRS's units.
+    ///
+    /// Computed as the square root of the local areal scale, so it does not depend on direction.
+    /// For conformal projections this is the exact linear scale; for the equal-area projection,
+    /// where the scale along and across parallels differ, it is their geometric mean.
+    pub fn units_per_meter(&self, lonlat: Coord<f64>) -> Option<f64> {
+        let east = Coord {
+            x: lonlat.x + 1e-4,
+            y: lonlat.y,
+        };
+        let north = Coord {
+            x: lonlat.x,
+            y: lonlat.y + 1e-4,
+        };
+        let origin = self.forward(lonlat)?;
+        let (east_step, north_step) = (self.forward(east)? - origin, self.forward(north)? - origin);
+        let projected_area = (east_step.x * north_step.y - east_step.y * north_step.x).abs();
+        let ground_area = Haversine.distance(Point::from(lonlat), Point::from(east))
+            * Haversine.distance(Point::from(lonlat), Point::from(north));
+        Some((projected_area / ground_area).sqrt())
+    }
+}
+
+impl fmt::Display for WorkingCrs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WorkingCrs::Wgs84 => write!(f, "WGS84 (degrees)"),
+            WorkingCrs::WebMercator => write!(f, "Web Mercator (EPSG:3857)"),
+            WorkingCrs::Utm { zone, north } => {
+                write!(f, "UTM zone {}{}", zone, if *north { 'N' } else { 'S' })
+            }
+            WorkingCrs::LocalEqualArea {
+                center_lon,
+                center_lat,
+            } => write!(f, "Local equal-area ({:.4}, {:.4})", center_lon, center_lat),
+        }
+    }
+}
+
+fn eccentricity_squared() -> f64 {
+    WGS84_F * (2.0 - WGS84_F)
+}
+
+fn web_mercator_forward(lonlat: Coord<f64>) -> Option<Coord<f64>> {
+    if lonlat.y.abs() > WEB_MERCATOR_MAX_LAT {
+        return None;
+    }
+    let x = WGS84_A * lonlat.x.to_radians();
+    let y = WGS84_A
+        * (std::f64::consts::FRAC_PI_4 + lonlat.y.to_radians() / 2.0)
+            .tan()
+            .ln();
+    Some(Coord { x, y })
+}
+
+fn web_mercator_inverse(projected: Coord<f64>) -> Coord<f64> {
+    let lon = (projected.x / WGS84_A).to_degrees();
+    let lat = (2.0 * (projected.y / WGS84_A).exp().atan() - FRAC_PI_2).to_degrees();
+    Coord { x: lon, y: lat }
+}
+
+fn utm_central_meridian(zone: u8) -> f64 {
+    (f64::from(zone) * 6.0 - 183.0).to_radians()
+}
+
+/// Snyder (8-9) to (8-10).
+fn utm_forward(lonlat: Coord<f64>, zone: u8, north: bool) -> Coord<f64> {
+    let e2 = eccentricity_squared();
+    let e4 = e2 * e2;
+    let e6 = e4 * e2;
+    let ep2 = e2 / (1.0 - e2);
+
+    let phi = lonlat.y.to_radians();
+    let lambda = lonlat.x.to_radians();
+    let (sin_phi, cos_phi) = phi.sin_cos();
+
+    let n = WGS84_A / (1.0 - e2 * sin_phi * sin_phi).sqrt();
+    let t = phi.tan().powi(2);
+    let c = ep2 * cos_phi * cos_phi;
+    let a = (lambda - utm_central_meridian(zone)) * cos_phi;
+    let m = WGS84_A
+        * ((1.0 - e2 / 4.0 - 3.0 * e4 / 64.0 - 5.0 * e6 / 256.0) * phi
+            - (3.0 * e2 / 8.0 + 3.0 * e4 / 32.0 + 45.0 * e6 / 1024.0) * (2.0 * phi).sin()
+            + (15.0 * e4 / 256.0 + 45.0 * e6 / 1024.0) * (4.0 * phi).sin()
+            - (35.0 * e6 / 3072.0) * (6.0 * phi).sin());
+
+    let x = UTM_K0
+        * n
+        * (a + (1.0 - t + c) * a.powi(3) / 6.0
+            + (5.0 - 18.0 * t + t * t + 72.0 * c - 58.0 * ep2) * a.powi(5) / 120.0)
+        + UTM_FALSE_EASTING;
+    let y = UTM_K0
+        * (m + n
+            * phi.tan()
+            * (a * a / 2.0
+                + (5.0 - t + 9.0 * c + 4.0 * c * c) * a.powi(4) / 24.0
+                + (61.0 - 58.0 * t + t * t + 600.0 * c - 330.0 * ep2) * a.powi(6) / 720.0));
+    let false_northing = if north { 0.0 } else { UTM_FALSE_NORTHING_SOUTH };
+
+    Coord {
+        x,
+        y: y + false_northing,
+    }
+}
+
+/// Snyder (8-11) to (8-25).
+fn utm_inverse(projected: Coord<f64>, zone: u8, north: bool) -> Coord<f64> {
+    let e2 = eccentricity_squared();
+    let e4 = e2 * e2;
+    let e6 = e4 * e2;
+    let ep2 = e2 / (1.0 - e2);
+    let e1 = (1.0 - (1.0 - e2).sqrt()) / (1.0 + (1.0 - e2).sqrt());
+
+    let false_northing = if north { 0.0 } else { UTM_FALSE_NORTHING_SOUTH };
+    let m = (projected.y - false_northing) / UTM_K0;
+    let mu = m / (WGS84_A * (1.0 - e2 / 4.0 - 3.0 * e4 / 64.0 - 5.0 * e6 / 256.0));
+    let phi1 = mu
+        + (3.0 * e1 / 2.0 - 27.0 * e1.powi(3) / 32.0) * (2.0 * mu).sin()
+        + (21.0 * e1 * e1 / 16.0 - 55.0 * e1.powi(4) / 32.0) * (4.0 * mu).sin()
+        + (151.0 * e1.powi(3) / 96.0) * (6.0 * mu).sin()
+        + (1097.0 * e1.powi(4) / 512.0) * (8.0 * mu).sin();
+
+    let (sin_phi1, cos_phi1) = phi1.sin_cos();
+    let c1 = ep2 * cos_phi1 * cos_phi1;
+    let t1 = phi1.tan().powi(2);
+    let n1 = WGS84_A / (1.0 - e2 * sin_phi1 * sin_phi1).sqrt();
+    let r1 = WGS84_A * (1.0 - e2) / (1.0 - e2 * sin_phi1 * sin_phi1).powf(1.5);
+    let d = (projected.x - UTM_FALSE_EASTING) / (n1 * UTM_K0);
+
+    let phi = phi1
+        - (n1 * phi1.tan() / r1)
+            * (d * d / 2.0
+                - (5.0 + 3.0 * t1 + 10.0 * c1 - 4.0 * c1 * c1 - 9.0 * ep2) * d.powi(4) / 24.0
+                + (61.0 + 90.0 * t1 + 298.0 * c1 + 45.0 * t1 * t1 - 252.0 * ep2 - 3.0 * c1 * c1)
+                    * d.powi(6)
+                    / 720.0);
+    let lambda = utm_central_meridian(zone)
+        + (d - (1.0 + 2.0 * t1 + c1) * d.powi(3) / 6.0
+            + (5.0 - 2.0 * c1 + 28.0 * t1 - 3.0 * c1 * c1 + 8.0 * ep2 + 24.0 * t1 * t1)
+                * d.powi(5)
+                / 120.0)
+            / cos_phi1;
+
+    Coord {
+        x: lambda.to_degrees(),
+        y: phi.to_degrees(),
+    }
+}
+
+/// Snyder (3-12): `q` as a function of geodetic latitude.
+fn authalic_q(sin_phi: f64) -> f64 {
+    let e2 = eccentricity_squared();
+    let e = e2.sqrt();
+    (1.0 - e2)
+        * (sin_phi / (1.0 - e2 * sin_phi * sin_phi)
+            - (1.0 / (2.0 * e)) * ((1.0 - e * sin_phi) / (1.0 + e * sin_phi)).ln())
+}
+
+/// Parameters shared by the forward and inverse oblique LAEA formulas, Snyder (24-2) to (24-20).
+struct LaeaParams {
+    lambda0: f64,
+    sin_beta1: f64,
+    cos_beta1: f64,
+    rq: f64,
+    d: f64,
+    qp: f64,
+}
+
+impl LaeaParams {
+    fn new(center_lon: f64, center_lat: f64) -> Self {
+        let e2 = eccentricity_squared();
+        let phi1 = center_lat.to_radians();
+        let (sin_phi1, cos_phi1) = phi1.sin_cos();
+        let qp = authalic_q(1.0);
+        let beta1 = (authalic_q(sin_phi1) / qp).clamp(-1.0, 1.0).asin();
+        let rq = WGS84_A * (qp / 2.0).sqrt();
+        let m1 = cos_phi1 / (1.0 - e2 * sin_phi1 * sin_phi1).sqrt();
+        let (sin_beta1, cos_beta1) = beta1.sin_cos();
+        // At the poles `m1` and `cos_beta1` both vanish; their ratio tends to 1.
+        let d = if cos_beta1.abs() < 1e-12 {
+            1.0
+        } else {
+            WGS84_A * m1 / (rq * cos_beta1)
+        };
+        LaeaParams {
+            lambda0: center_lon.to_radians(),
+            sin_beta1,
+            cos_beta1,
+            rq,
+            d,
+            qp,
+        }
+    }
+}
+
+fn laea_forward(lonlat: Coord<f64>, center_lon: f64, center_lat: f64) -> Option<Coord<f64>> {
+    let params = LaeaParams::new(center_lon, center_lat);
+    let beta = (authalic_q(lonlat.y.to_radians().sin()) / params.qp)
+        .clamp(-1.0, 1.0)
+        .asin();
+    let (sin_beta, cos_beta) = beta.sin_cos();
+    let (sin_dlambda, cos_dlambda) = (lonlat.x.to_radians() - params.lambda0).sin_cos();
+
+    let denominator = 1.0 + params.sin_beta1 * sin_beta + params.cos_beta1 * cos_beta * cos_dlambda;
+    // The antipode of the center cannot be projected.
+    if denominator <= 1e-12 {
+        return None;
+    }
+    let b = params.rq * (2.0 / denominator).sqrt();
+
+    Some(Coord {
+        x: b * params.d * cos_beta * sin_dlambda,
+        y: (b / params.d)
+            * (params.cos_beta1 * sin_beta - params.sin_beta1 * cos_beta * cos_dlambda),
+    })
+}
+
+fn laea_inverse(projected: Coord<f64>, center_lon: f64, center_lat: f64) -> Option<Coord<f64>> {
+    let params = LaeaParams::new(center_lon, center_lat);
+    let (x, y) = (projected.x, projected.y);
+    let rho = ((x / params.d).powi(2) + (params.d * y).powi(2)).sqrt();
+    if rho < 1e-9 {
+        return Some(Coord {
+            x: center_lon,
+            y: center_lat,
+        });
+    }
+    let ratio = rho / (2.0 * params.rq);
+    if ratio > 1.0 {
+        return None;
+    }
+    let ce = 2.0 * ratio.asin();
+    let (sin_ce, cos_ce) = ce.sin_cos();
+
+    let beta = (cos_ce * params.sin_beta1 + params.d * y * sin_ce * params.cos_beta1 / rho)
+        .clamp(-1.0, 1.0)
+        .asin();
+    let lambda = params.lambda0
+        + (x * sin_ce).atan2(
+            params.d * rho * params.cos_beta1 * cos_ce
+                - params.d * params.d * y * params.sin_beta1 * sin_ce,
+        );
+
+    // Snyder (3-18): geodetic from authalic latitude.
+    let e2 = eccentricity_squared();
+    let e4 = e2 * e2;
+    let e6 = e4 * e2;
+    let phi = beta
+        + (e2 / 3.0 + 31.0 * e4 / 180.0 + 517.0 * e6 / 5040.0) * (2.0 * beta).sin()
+        + (23.0 * e4 / 360.0 + 251.0 * e6 / 3780.0) * (4.0 * beta).sin()
+        + (761.0 * e6 / 45360.0) * (6.0 * beta).sin();
+
+    Some(Coord {
+        x: lambda.to_degrees(),
+        y: phi.to_degrees(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_coord_close(actual: Coord<f64>, expected: Coord<f64>, tolerance: f64) {
+        assert!(
+            (actual.x - expected.x).abs() < tolerance && (actual.y - expected.y).abs() < tolerance,
+            "{:?} is not within {} of {:?}",
+            actual,
+            tolerance,
+            expected
+        );
+    }
+
+    #[test]
+    fn utm_central_meridian_at_equator_is_false_origin() {
+        let crs = WorkingCrs::utm_for(3.0, 0.0);
+        assert_eq!(
+            crs,
+            WorkingCrs::Utm {
+                zone: 31,
+                north: true
+            }
+        );
+        let projected = crs.forward(Coord { x: 3.0, y: 0.0 }).unwrap();
+        assert_coord_close(
+            projected,
+            Coord {
+                x: 500_000.0,
+                y: 0.0,
+            },
+            1e-6,
+        );
+    }
+
+    #[test]
+    fn utm_matches_published_position() {
+        // The CN Tower, 43°38′33.24″N 79°23′13.7″W, is at 17T 630084 4833438.
+        let lonlat = Coord {
+            x: -(79.0 + 23.0 / 60.0 + 13.7 / 3600.0),
+            y: 43.0 + 38.0 / 60.0 + 33.24 / 3600.0,
+        };
+        let crs = WorkingCrs::utm_for(lonlat.x, lonlat.y);
+        assert_eq!(
+            crs,
+            WorkingCrs::Utm {
+                zone: 17,
+                north: true
+            }
+        );
+        let projected = crs.forward(lonlat).unwrap();
+        assert_coord_close(
+            projected,
+            Coord {
+                x: 630_084.0,
+                y: 4_833_438.0,
+            },
+            1.0,
+        );
+    }
+
+    #[test]
+    fn utm_round_trips() {
+        for (lon, lat) in [
+            (128.9784, 37.566),
+            (-79.387, 43.643),
+            (18.42, -33.92),
+            (-3.5, 80.0),
+        ] {
+            let lonlat = Coord { x: lon, y: lat };
+            let crs = WorkingCrs::utm_for(lon, lat);
+            let round_trip = crs.inverse(crs.forward(lonlat).unwrap()).unwrap();
+            assert_coord_close(round_trip, lonlat, 1e-7);
+        }
+    }
+
+    #[test]
+    fn laea_matches_epsg_example() {
+        // EPSG Guidance Note 7-2 example for ETRS89-LAEA, without its false easting and northing.
+        let crs = WorkingCrs::LocalEqualArea {
+            center_lon: 10.0,
+            center_lat: 52.0,
+        };
+        let projected = crs.forward(Coord { x: 5.0, y: 50.0 }).unwrap();
+        let expected = Coord {
+            x: 3_962_799.45 - 4_321_000.0,
+            y: 2_999_718.85 - 3_210_000.0,
+        };
+        assert_coord_close(projected, expected, 0.01);
+    }
+
+    #[test]
+    fn laea_round_trips() {
+        for (center_lon, center_lat) in [(10.0, 52.0), (-120.0, -30.0), (0.0, 90.0)] {
+            let crs = WorkingCrs::LocalEqualArea {
+                center_lon,
+                center_lat,
+            };
+            assert_coord_close(
+                crs.forward(Coord {
+                    x: center_lon,
+                    y: center_lat,
+                })
+                .unwrap(),
+                Coord { x: 0.0, y: 0.0 },
+                1e-6,
+            );
+            for (dlon, dlat) in [(0.5, 0.5), (-2.0, 1.0), (3.0, -4.0)] {
+                let lonlat = Coord {
+                    x: center_lon + dlon,
+                    y: (center_lat + dlat).min(89.0),
+                };
+                let round_trip = crs.inverse(crs.forward(lonlat).unwrap()).unwrap();
+                assert_coord_close(round_trip, lonlat, 1e-7);
+            }
+        }
+    }
+
+    #[test]
+    fn units_per_meter_is_direction_independent_for_equal_area() {
+        let crs = WorkingCrs::LocalEqualArea {
+            center_lon: 10.0,
+            center_lat: 52.0,
+        };
+        // Here the scale along the parallel is about 1.4% too large and the one across it about
+        // 1.2% too small, but their geometric mean stays at 1. The tolerance covers the spherical
+        // ground distances.
+        let scale = crs.units_per_meter(Coord { x: 40.0, y: 30.0 }).unwrap();
+        assert!((scale - 1.0).abs() < 5e-3, "scale {}", scale);
+    }
+}
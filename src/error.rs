@@ -0,0 +1,165 @@
+//! Application error type and the log that surfaces errors in the egui window. Stderr is not
+//! visible on wasm, so anything that can fail at runtime is pushed to the [`ErrorLog`] instead.
+
+use std::fmt;
+use std::sync::{Arc, RwLock};
+
+use galileo::layer::FeatureId;
+
+use crate::input::VertexRef;
+
+#[derive(Debug)]
+pub enum AppError {
+    TileLayerCreationFailed(String),
+    ProjectionUnavailable,
+    InitialPointProjectionFailed { lon: f64, lat: f64 },
+    PointLayerNotFound,
+    LineLayerNotFound,
+    InitFailed(String),
+    Drag(DragError),
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::TileLayerCreationFailed(reason) => {
+                write!(f, "failed to create the tile layer: {}", reason)
+            }
+            AppError::ProjectionUnavailable => {
+                write!(f, "no projection is available for the map CRS")
+            }
+            AppError::InitialPointProjectionFailed { lon, lat } => {
+                write!(f, "failed to project initial point ({}, {})", lon, lat)
+            }
+            AppError::PointLayerNotFound => {
+                write!(f, "the draggable point layer is missing from the map")
+            }
+            AppError::LineLayerNotFound => write!(f, "the line layer is missing from the map"),
+            AppError::InitFailed(reason) => write!(f, "failed to initialize the app: {}", reason),
+            AppError::Drag(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for AppError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AppError::Drag(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<DragError> for AppError {
+    fn from(e: DragError) -> Self {
+        AppError::Drag(e)
+    }
+}
+
+#[derive(Debug)]
+pub enum DragError {
+    ScreenToMapConversionFailed,
+    SelectedFeatureIdMissing,
+    // Point update related errors
+    PointFeatureNotFoundInLayer(FeatureId),
+    FailedToUpdateSharedPointIndex(FeatureId, VertexRef),
+    FailedToFindSharedPointId(FeatureId),
+    // Line update related errors
+    LineIdUnavailable,
+    ProjectionUnavailable,
+    ProjectionFailed,
+    UnprojectionFailed,
+    LineFeatureNotFoundInLayer(FeatureId),
+    LineLayerNotFound,
+}
+
+impl fmt::Display for DragError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DragError::ScreenToMapConversionFailed => {
+                write!(
+                    f,
+                    "failed to convert the pointer position to map coordinates"
+                )
+            }
+            DragError::SelectedFeatureIdMissing => write!(f, "no point is selected"),
+            DragError::PointFeatureNotFoundInLayer(id) => {
+                write!(f, "point feature {:?} is not in the point layer", id)
+            }
+            DragError::FailedToUpdateSharedPointIndex(id, vertex) => write!(
+                f,
+                "point feature {:?} maps to missing vertex {:?}",
+                id, vertex
+            ),
+            DragError::FailedToFindSharedPointId(id) => {
+                write!(f, "point feature {:?} has no input vertex", id)
+            }
+            DragError::LineIdUnavailable => write!(f, "the line feature id of a ring is unknown"),
+            DragError::ProjectionUnavailable => {
+                write!(f, "no projection is available for the map CRS")
+            }
+            DragError::ProjectionFailed => write!(f, "failed to project a point into the map CRS"),
+            DragError::UnprojectionFailed => {
+                write!(f, "failed to unproject a point from the map CRS")
+            }
+            DragError::LineFeatureNotFoundInLayer(id) => {
+                write!(f, "line feature {:?} is not in the line layer", id)
+            }
+            DragError::LineLayerNotFound => write!(f, "the line layer is missing from the map"),
+        }
+    }
+}
+
+impl std::error::Error for DragError {}
+
+#[derive(Debug, Clone)]
+pub struct LogEntry {
+    /// Increases by one for every pushed entry, so the window can tell which ones are new.
+    pub sequence: u64,
+    pub message: String,
+}
+
+/// Shared list of runtime errors shown in the egui window.
+#[derive(Debug, Clone, Default)]
+pub struct ErrorLog {
+    inner: Arc<RwLock<ErrorLogInner>>,
+}
+
+#[derive(Debug, Default)]
+struct ErrorLogInner {
+    entries: Vec<LogEntry>,
+    next_sequence: u64,
+}
+
+/// Older entries are dropped once the log grows past this, so a failure that repeats on every
+/// drag event cannot grow it without bound.
+const MAX_LOG_ENTRIES: usize = 200;
+
+impl ErrorLog {
+    /// Records `error` with a short description of what was being done, e.g. "dragging a point".
+    /// The window's log and toast are the only places it is shown.
+    pub fn push(&self, context: &str, error: impl fmt::Display) {
+        let message = format!("Error while {}: {}", context, error);
+
+        let mut inner = self.inner.write().unwrap();
+        let sequence = inner.next_sequence;
+        inner.next_sequence += 1;
+        inner.entries.push(LogEntry { sequence, message });
+        if inner.entries.len() > MAX_LOG_ENTRIES {
+            let overflow = inner.entries.len() - MAX_LOG_ENTRIES;
+            inner.entries.drain(..overflow);
+        }
+    }
+
+    pub fn entries(&self) -> Vec<LogEntry> {
+        self.inner.read().unwrap().entries.clone()
+    }
+
+    pub fn latest(&self) -> Option<LogEntry> {
+        self.inner.read().unwrap().entries.last().cloned()
+    }
+
+    pub fn clear(&self) {
+        self.inner.write().unwrap().entries.clear();
+    }
+}
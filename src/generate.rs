@@ -0,0 +1,243 @@
+//! Procedural test inputs. Each generator produces an [`InputGeometryConfig`], the same model the
+//! configured inputs are built from, so generated geometries are added to the map and dragged like
+//! any other input.
+
+use std::f64::consts::TAU;
+use std::fmt;
+
+use crate::random::SeededRng;
+use crate::{GeometryConfig, InputGeometryConfig, PointConfig};
+
+/// Meters per degree of latitude, close enough for placing generated vertices.
+const METERS_PER_DEGREE: f64 = 111_320.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GeneratorKind {
+    RandomPoints,
+    RandomWalk,
+    /// A star-shaped polygon with random radii, which is always simple.
+    RandomPolygon,
+    StarPolygon,
+    PolygonWithHoles,
+    /// A polygon in which every vertex appears twice in a row.
+    DuplicateVertices,
+    /// A line whose vertices all lie on one straight line in lon/lat degrees. After projecting
+    /// into a working CRS they are only nearly collinear, so planar algorithms can see tiny turns.
+    Collinear,
+    /// A polygon that runs out along a line and back again, enclosing no area. It has the vertex
+    /// count rounded down to an even number, and at least four vertices.
+    ZeroArea,
+}
+
+impl GeneratorKind {
+    pub const ALL: [GeneratorKind; 8] = [
+        GeneratorKind::RandomPoints,
+        GeneratorKind::RandomWalk,
+        GeneratorKind::RandomPolygon,
+        GeneratorKind::StarPolygon,
+        GeneratorKind::PolygonWithHoles,
+        GeneratorKind::DuplicateVertices,
+        GeneratorKind::Collinear,
+        GeneratorKind::ZeroArea,
+    ];
+}
+
+impl fmt::Display for GeneratorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            GeneratorKind::RandomPoints => "Random points",
+            GeneratorKind::RandomWalk => "Random walk",
+            GeneratorKind::RandomPolygon => "Random polygon",
+            GeneratorKind::StarPolygon => "Star polygon",
+            GeneratorKind::PolygonWithHoles => "Polygon with holes",
+            GeneratorKind::DuplicateVertices => "Duplicate vertices",
+            GeneratorKind::Collinear => "Collinear",
+            GeneratorKind::ZeroArea => "Zero area",
+        };
+        f.write_str(name)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GeneratorSettings {
+    pub kind: GeneratorKind,
+    /// Vertices of the geometry, or of the exterior ring for polygons.
+    pub vertex_count: usize,
+    pub seed: u64,
+    /// How far the vertices spread from the center.
+    pub radius_meters: f64,
+}
+
+impl Default for GeneratorSettings {
+    fn default() -> Self {
+        GeneratorSettings {
+            kind: GeneratorKind::RandomPolygon,
+            vertex_count: 12,
+            seed: 1,
+            radius_meters: 20_000.0,
+        }
+    }
+}
+
+/// Generates an input around `center`.
+pub fn generate(settings: &GeneratorSettings, center: PointConfig) -> InputGeometryConfig {
+    let mut rng = SeededRng::new(settings.seed);
+    // Offsets are generated in meters east and north of the center.
+    let to_lonlat = |east: f64, north: f64| {
+        let lat = center.lat + north / METERS_PER_DEGREE;
+        let lon = center.lon + east / (METERS_PER_DEGREE * center.lat.to_radians().cos());
+        PointConfig::new(lon, lat)
+    };
+    let polar = |angle: f64, radius: f64| to_lonlat(radius * angle.cos(), radius * angle.sin());
+    let radius = settings.radius_meters;
+    let n = settings.vertex_count.max(3);
+
+    let geometry = match settings.kind {
+        GeneratorKind::RandomPoints => GeometryConfig::Points(
+            (0..n)
+                .map(|_| to_lonlat(rng.range(-radius, radius), rng.range(-radius, radius)))
+                .collect(),
+        ),
+        GeneratorKind::RandomWalk => {
+            let step = radius / (n as f64).sqrt();
+            let (mut east, mut north) = (0.0, 0.0);
+            GeometryConfig::LineString(
+                (0..n)
+                    .map(|_| {
+                        let point = to_lonlat(east, north);
+                        let heading = rng.range(0.0, TAU);
+                        east += step * heading.cos();
+                        north += step * heading.sin();
+                        point
+                    })
+                    .collect(),
+            )
+        }
+        GeneratorKind::RandomPolygon => GeometryConfig::Polygon {
+            exterior: star_shaped_ring(&mut rng, n)
+                .into_iter()
+                .map(|(angle, scale)| polar(angle, radius * scale))
+                .collect(),
+            interiors: Vec::new(),
+        },
+        GeneratorKind::StarPolygon => GeometryConfig::Polygon {
+            // Points alternate between the outer and the inner radius.
+            exterior: (0..n * 2)
+                .map(|i| {
+                    let scale = if i % 2 == 0 { 1.0 } else { 0.45 };
+                    polar(TAU * i as f64 / (n * 2) as f64, radius * scale)
+                })
+                .collect(),
+            interiors: Vec::new(),
+        },
+        GeneratorKind::PolygonWithHoles => {
+            // Evenly spaced angles keep every gap below half a turn, so the center is inside the
+            // exterior, and so is the disc out to the nearest exterior edge. Both holes stay
+            // within half of that distance from the center.
+            let offsets: Vec<(f64, f64)> = jittered_ring(&mut rng, n)
+                .into_iter()
+                .map(|(angle, scale)| (radius * scale * angle.cos(), radius * scale * angle.sin()))
+                .collect();
+            let clearance = offsets
+                .iter()
+                .zip(offsets.iter().cycle().skip(1))
+                .map(|(start, end)| distance_from_origin(*start, *end))
+                .fold(f64::INFINITY, f64::min);
+            let hole = |center_east: f64| -> Vec<PointConfig> {
+                (0..6)
+                    .map(|i| {
+                        // Holes run clockwise, opposite to the exterior.
+                        let angle = -TAU * i as f64 / 6.0;
+                        to_lonlat(
+                            center_east + clearance * 0.2 * angle.cos(),
+                            clearance * 0.2 * angle.sin(),
+                        )
+                    })
+                    .collect()
+            };
+            GeometryConfig::Polygon {
+                exterior: offsets
+                    .into_iter()
+                    .map(|(east, north)| to_lonlat(east, north))
+                    .collect(),
+                interiors: vec![hole(-clearance * 0.3), hole(clearance * 0.3)],
+            }
+        }
+        GeneratorKind::DuplicateVertices => GeometryConfig::Polygon {
+            exterior: star_shaped_ring(&mut rng, n)
+                .into_iter()
+                .flat_map(|(angle, scale)| {
+                    let point = polar(angle, radius * scale);
+                    [point, point]
+                })
+                .collect(),
+            interiors: Vec::new(),
+        },
+        GeneratorKind::Collinear => {
+            let heading = rng.range(0.0, TAU);
+            let mut offsets: Vec<f64> = (0..n).map(|_| rng.range(-radius, radius)).collect();
+            offsets.sort_by(f64::total_cmp);
+            GeometryConfig::LineString(
+                offsets
+                    .into_iter()
+                    .map(|offset| polar(heading, offset))
+                    .collect(),
+            )
+        }
+        GeneratorKind::ZeroArea => {
+            let heading = rng.range(0.0, TAU);
+            // Going out along `m` points and back along all but the two ends gives `2m - 2`
+            // vertices. Three points out is the least that still makes a ring.
+            let m = (n / 2 + 1).max(3);
+            let outward: Vec<PointConfig> = (0..m)
+                .map(|i| polar(heading, radius * i as f64 / (m - 1) as f64))
+                .collect();
+            // Back along the same points, without repeating the far end or the start.
+            let back = outward[1..outward.len() - 1].iter().rev().copied();
+            GeometryConfig::Polygon {
+                exterior: outward.iter().copied().chain(back).collect(),
+                interiors: Vec::new(),
+            }
+        }
+    };
+
+    InputGeometryConfig {
+        name: format!("{} (seed {})", settings.kind, settings.seed),
+        geometry,
+    }
+}
+
+/// `n` (angle, radius scale) pairs at increasing angles, counter-clockwise, with scales in
+/// `0.6..1.0`.
+fn star_shaped_ring(rng: &mut SeededRng, n: usize) -> Vec<(f64, f64)> {
+    let mut angles: Vec<f64> = (0..n).map(|_| rng.range(0.0, TAU)).collect();
+    angles.sort_by(f64::total_cmp);
+    angles
+        .into_iter()
+        .map(|angle| (angle, rng.range(0.6, 1.0)))
+        .collect()
+}
+
+/// Like [`star_shaped_ring`], but with the angles evenly spaced and jittered by at most a fifth of
+/// the spacing, so no two consecutive vertices are half a turn or more apart.
+fn jittered_ring(rng: &mut SeededRng, n: usize) -> Vec<(f64, f64)> {
+    let step = TAU / n as f64;
+    (0..n)
+        .map(|i| {
+            let angle = step * i as f64 + rng.range(-0.2, 0.2) * step;
+            (angle, rng.range(0.6, 1.0))
+        })
+        .collect()
+}
+
+/// Distance from the origin to the segment between two offsets.
+fn distance_from_origin(start: (f64, f64), end: (f64, f64)) -> f64 {
+    let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+    let length_squared = dx * dx + dy * dy;
+    let t = if length_squared == 0.0 {
+        0.0
+    } else {
+        (-(start.0 * dx + start.1 * dy) / length_squared).clamp(0.0, 1.0)
+    };
+    (start.0 + t * dx).hypot(start.1 + t * dy)
+}
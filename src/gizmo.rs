@@ -0,0 +1,100 @@
+//! On-map handles for the affine transform parameters. The egui window paints them and the map
+//! event handler drags them, in the same way as input vertices.
+
+use galileo_types::cartesian::{CartesianPoint2d, Point2};
+use galileo_types::geo::{Crs, GeoPoint};
+
+use crate::algorithms::{AffineParams, TransformOrigin};
+use crate::unproject_cartesian_point_to_geo;
+
+/// Distance of the rotation and scale handles from the origin, in screen pixels at scale 1.
+const HANDLE_DISTANCE_PX: f64 = 70.0;
+/// How close to a handle, in screen pixels, a drag has to start to grab it.
+const HANDLE_TOLERANCE_PX: f64 = 10.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GizmoHandle {
+    /// Moves the transform origin.
+    Origin,
+    /// Sets the rotation angle from the direction of the pointer.
+    Rotate,
+    /// Sets the x and y scale factors from the pointer's offset.
+    Scale,
+}
+
+/// Positions of the handles in the map CRS.
+#[derive(Debug, Clone, Copy)]
+pub struct GizmoLayout {
+    pub origin: Point2,
+    pub rotate: Point2,
+    pub scale: Point2,
+}
+
+impl GizmoLayout {
+    /// `origin` is the transform origin in the map CRS and `resolution` the map units per pixel.
+    pub fn new(origin: Point2, params: &AffineParams, resolution: f64) -> Self {
+        let distance = HANDLE_DISTANCE_PX * resolution;
+        let angle = params.angle.to_radians();
+        GizmoLayout {
+            origin,
+            rotate: Point2::new(
+                origin.x() + distance * angle.cos(),
+                origin.y() + distance * angle.sin(),
+            ),
+            // Below and to the right of the origin, so it starts clear of the rotation handle.
+            scale: Point2::new(
+                origin.x() + distance * params.scale_x,
+                origin.y() - distance * params.scale_y,
+            ),
+        }
+    }
+
+    /// The handle under `position`, if any. The origin handle wins ties, so the gizmo can always be
+    /// moved away from a collapsed state.
+    pub fn handle_at(&self, position: &Point2, resolution: f64) -> Option<GizmoHandle> {
+        let tolerance = HANDLE_TOLERANCE_PX * resolution;
+        [
+            (GizmoHandle::Origin, self.origin),
+            (GizmoHandle::Rotate, self.rotate),
+            (GizmoHandle::Scale, self.scale),
+        ]
+        .into_iter()
+        .find(|(_, handle)| distance(handle, position) <= tolerance)
+        .map(|(handle, _)| handle)
+    }
+}
+
+/// Updates `params` for `handle` being dragged to `position` (map CRS).
+pub fn drag_handle(
+    handle: GizmoHandle,
+    position: &Point2,
+    layout: &GizmoLayout,
+    params: &mut AffineParams,
+    resolution: f64,
+    map_crs: &Crs,
+) {
+    let dx = position.x() - layout.origin.x();
+    let dy = position.y() - layout.origin.y();
+    match handle {
+        GizmoHandle::Origin => {
+            if let Ok(geo_point) = unproject_cartesian_point_to_geo(position, map_crs) {
+                params.origin = TransformOrigin::Custom(geo::coord! {
+                    x: geo_point.lon(),
+                    y: geo_point.lat(),
+                });
+            }
+        }
+        GizmoHandle::Rotate => {
+            params.angle = dy.atan2(dx).to_degrees();
+        }
+        GizmoHandle::Scale => {
+            let distance = HANDLE_DISTANCE_PX * resolution;
+            params.scale_x = dx / distance;
+            params.scale_y = -dy / distance;
+        }
+    }
+}
+
+fn distance(a: &Point2, b: &Point2) -> f64 {
+    (a.x() - b.x()).hypot(a.y() - b.y())
+}
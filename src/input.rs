@@ -0,0 +1,196 @@
+//! Runtime state of the editable input geometries.
+//!
+//! Vertices are kept in the map CRS, like the draggable point features they mirror. Algorithms
+//! get a WGS84 copy through [`InputGeometry::to_geo`].
+
+use galileo_types::cartesian::Point2;
+use galileo_types::geo::impls::GeoPoint2d;
+use galileo_types::geo::{Crs, GeoPoint, NewGeoPoint};
+use geo::{Coord, Geometry, LineString, MultiPoint, Point, Polygon};
+
+use crate::antimeridian;
+use crate::error::{AppError, DragError};
+use crate::{GeometryConfig, InputGeometryConfig, PointConfig};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputKind {
+    Points,
+    LineString,
+    Polygon,
+}
+
+#[derive(Debug, Clone)]
+pub struct InputGeometry {
+    pub name: String,
+    pub kind: InputKind,
+    /// Vertices in the map CRS. Points and line strings have exactly one ring. For polygons the
+    /// first ring is the exterior and the rest are holes; rings are stored without repeating the
+    /// first vertex at the end.
+    pub rings: Vec<Vec<Point2>>,
+}
+
+/// Identifies one vertex of one ring of one input geometry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct VertexRef {
+    pub geometry: usize,
+    pub ring: usize,
+    pub vertex: usize,
+}
+
+impl InputGeometry {
+    pub fn from_config(config: &InputGeometryConfig, crs: &Crs) -> Result<Self, AppError> {
+        let projection = crs
+            .get_projection::<GeoPoint2d, Point2>()
+            .ok_or(AppError::ProjectionUnavailable)?;
+        let project_ring = |ring: &[PointConfig]| -> Result<Vec<Point2>, AppError> {
+            ring.iter()
+                .map(|p| {
+                    projection.project(&GeoPoint2d::lonlat(p.lon, p.lat)).ok_or(
+                        AppError::InitialPointProjectionFailed {
+                            lon: p.lon,
+                            lat: p.lat,
+                        },
+                    )
+                })
+                .collect()
+        };
+
+        let (kind, rings) = match &config.geometry {
+            GeometryConfig::Points(points) => (InputKind::Points, vec![project_ring(points)?]),
+            GeometryConfig::LineString(points) => {
+                (InputKind::LineString, vec![project_ring(points)?])
+            }
+            GeometryConfig::Polygon {
+                exterior,
+                interiors,
+            } => {
+                let mut rings = vec![project_ring(exterior)?];
+                for interior in interiors {
+                    rings.push(project_ring(interior)?);
+                }
+                (InputKind::Polygon, rings)
+            }
+        };
+
+        Ok(InputGeometry {
+            name: config.name.clone(),
+            kind,
+            rings,
+        })
+    }
+
+    /// Whether the ring is drawn and measured as closed, i.e. with a segment from the last vertex
+    /// back to the first.
+    pub fn rings_are_closed(&self) -> bool {
+        self.kind == InputKind::Polygon
+    }
+
+    /// Whether the ring is drawn as a line on the map. Point sets have no line.
+    pub fn has_contours(&self) -> bool {
+        self.kind != InputKind::Points
+    }
+
+    pub fn ring_to_lonlat(&self, ring: usize, crs: &Crs) -> Result<Vec<Coord<f64>>, DragError> {
+        let projection = crs
+            .get_projection::<GeoPoint2d, Point2>()
+            .ok_or(DragError::ProjectionUnavailable)?;
+        self.rings[ring]
+            .iter()
+            .map(|point| {
+                let geo_point = projection
+                    .unproject(point)
+                    .ok_or(DragError::UnprojectionFailed)?;
+                Ok(geo::coord!(x: geo_point.lon(), y: geo_point.lat()))
+            })
+            .collect()
+    }
+
+    /// Converts the geometry to a WGS84 `geo` geometry: a `MultiPoint`, `LineString` or `Polygon`.
+    ///
+    /// Lines and rings get continuous longitudes, which go past ±180° when they cross the
+    /// antimeridian, so that algorithms see the short way between neighbouring vertices. Holes are
+    /// unwrapped relative to the start of the exterior so they stay inside it.
+    pub fn to_geo(&self, crs: &Crs) -> Result<Geometry<f64>, DragError> {
+        let mut rings = (0..self.rings.len())
+            .map(|ring| self.ring_to_lonlat(ring, crs))
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter();
+        let mut first_ring = rings.next().unwrap_or_default();
+        if self.kind != InputKind::Points {
+            first_ring = antimeridian::unwrap_longitudes(&first_ring);
+        }
+        let reference = first_ring.first().copied();
+        let rings = rings.map(|ring| {
+            let unwrapped = antimeridian::unwrap_longitudes(
+                &reference.into_iter().chain(ring).collect::<Vec<_>>(),
+            );
+            unwrapped[usize::from(reference.is_some())..].to_vec()
+        });
+        Ok(match self.kind {
+            InputKind::Points => Geometry::MultiPoint(MultiPoint(
+                first_ring.into_iter().map(Point::from).collect(),
+            )),
+            InputKind::LineString => Geometry::LineString(LineString(first_ring)),
+            // `Polygon::new` closes the rings.
+            InputKind::Polygon => Geometry::Polygon(Polygon::new(
+                LineString(first_ring),
+                rings.map(LineString).collect(),
+            )),
+        })
+    }
+
+    /// Segments of a ring as pairs of vertex indices, including the closing segment of polygon
+    /// rings.
+    pub fn ring_segments(&self, ring: usize) -> Vec<(usize, usize)> {
+        let len = self.rings[ring].len();
+        let mut segments: Vec<(usize, usize)> = (1..len).map(|i| (i - 1, i)).collect();
+        if self.rings_are_closed() && len > 2 {
+            segments.push((len - 1, 0));
+        }
+        segments
+    }
+}
+
+/// All input vertices flattened into one list, as used for snapping.
+pub struct FlatVertices {
+    pub points: Vec<Point2>,
+    pub refs: Vec<VertexRef>,
+    /// Segments as pairs of indices into `points`.
+    pub segments: Vec<(usize, usize)>,
+}
+
+impl FlatVertices {
+    pub fn new(inputs: &[InputGeometry]) -> Self {
+        let mut flat = FlatVertices {
+            points: Vec::new(),
+            refs: Vec::new(),
+            segments: Vec::new(),
+        };
+        for (geometry_index, geometry) in inputs.iter().enumerate() {
+            for (ring_index, ring) in geometry.rings.iter().enumerate() {
+                let offset = flat.points.len();
+                for (vertex_index, point) in ring.iter().enumerate() {
+                    flat.points.push(*point);
+                    flat.refs.push(VertexRef {
+                        geometry: geometry_index,
+                        ring: ring_index,
+                        vertex: vertex_index,
+                    });
+                }
+                if geometry.has_contours() {
+                    flat.segments.extend(
+                        geometry
+                            .ring_segments(ring_index)
+                            .into_iter()
+                            .map(|(start, end)| (offset + start, offset + end)),
+                    );
+                }
+            }
+        }
+        flat
+    }
+
+    pub fn index_of(&self, vertex: VertexRef) -> Option<usize> {
+        self.refs.iter().position(|r| *r == vertex)
+    }
+}
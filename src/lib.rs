@@ -7,12 +7,11 @@ use galileo::control::{EventPropagation, UserEvent, UserEventHandler};
 use galileo::layer::raster_tile_layer::RasterTileLayerBuilder;
 use galileo::layer::{FeatureId, FeatureLayer, Layer};
 use galileo::symbol::{CirclePointSymbol, SimpleContourSymbol};
-use galileo::{Color, Map, MapBuilder};
+use galileo::{Color, Map, MapBuilder, MapView};
 use galileo_egui::InitBuilder; // EguiMapState and EguiMap are used in app_ui.rs
-use galileo_types::Disambiguate;
 use galileo_types::cartesian::Point2;
 use galileo_types::geo::impls::GeoPoint2d;
-use galileo_types::geo::{Crs, GeoPoint, NewGeoPoint};
+use galileo_types::geo::{Crs, NewGeoPoint};
 use galileo_types::geometry_type::{CartesianSpace2d, GeoSpace2d};
 use galileo_types::impls::Contour;
 
@@ -20,8 +19,23 @@ use galileo_types::impls::Contour;
 use wasm_bindgen::prelude::*;
 
 pub mod algorithms;
+pub mod antimeridian;
 pub mod app_ui; // Declare the new module // Declare the algorithms module
+pub mod crs;
+pub mod error;
+pub mod generate;
+pub mod gizmo;
+pub mod input;
+pub mod overlay;
+pub mod random;
+pub mod snapping;
+use algorithms::AffineParams;
 use app_ui::EguiMapApp; // Import the struct
+use crs::WorkingCrs;
+pub use error::{AppError, DragError, ErrorLog};
+use gizmo::{GizmoHandle, GizmoLayout};
+use input::{FlatVertices, InputGeometry, VertexRef};
+use snapping::{SnapResult, SnapSettings};
 
 // Configuration Structs
 #[derive(Debug, Clone, Copy)]
@@ -30,10 +44,29 @@ pub struct PointConfig {
     pub lat: f64,
 }
 
-#[derive(Debug, Clone, Copy)]
-pub struct LineConfig {
-    pub start: PointConfig,
-    pub end: PointConfig,
+impl PointConfig {
+    pub const fn new(lon: f64, lat: f64) -> Self {
+        PointConfig { lon, lat }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum GeometryConfig {
+    /// A set of unconnected points.
+    Points(Vec<PointConfig>),
+    LineString(Vec<PointConfig>),
+    /// Rings are listed without repeating their first point at the end.
+    Polygon {
+        exterior: Vec<PointConfig>,
+        interiors: Vec<Vec<PointConfig>>,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct InputGeometryConfig {
+    /// Shown in the egui window to tell inputs apart.
+    pub name: String,
+    pub geometry: GeometryConfig,
 }
 
 #[derive(Debug, Clone)]
@@ -41,12 +74,18 @@ pub struct MapViewConfig {
     pub center_lon: f64,
     pub center_lat: f64,
     pub zoom: u32,
+    /// Projection the map is displayed in, and the CRS of the draggable points. The OSM base
+    /// layer is only added when this is EPSG:3857, since its tiles are not reprojected.
+    pub map_crs: Crs,
+    /// Initial planar CRS that algorithms run Euclidean computations in. Can be changed from the
+    /// egui window.
+    pub working_crs: WorkingCrs,
 }
 
 #[derive(Debug, Clone)]
 pub struct MapGeometryConfig {
-    pub draggable_points: Vec<PointConfig>,
-    pub line: LineConfig,
+    /// Every vertex of every input is draggable on the map.
+    pub inputs: Vec<InputGeometryConfig>,
 }
 
 #[derive(Debug, Clone)]
@@ -62,156 +101,296 @@ impl Default for AppConfig {
                 center_lon: 128.9784,
                 center_lat: 37.566,
                 zoom: 8,
+                map_crs: Crs::EPSG3857,
+                working_crs: WorkingCrs::utm_for(128.9784, 37.566),
             },
             geometries: MapGeometryConfig {
-                draggable_points: vec![
-                    PointConfig {
-                        lon: 127.9784,
-                        lat: 37.566,
+                inputs: vec![
+                    InputGeometryConfig {
+                        name: "Line".to_string(),
+                        geometry: GeometryConfig::LineString(vec![
+                            PointConfig::new(127.9784, 37.566),
+                            PointConfig::new(128.9784, 37.566),
+                            PointConfig::new(129.1, 37.52),
+                            PointConfig::new(129.25, 37.6),
+                            PointConfig::new(129.4, 37.55),
+                            PointConfig::new(129.55, 37.75),
+                        ]),
                     },
-                    PointConfig {
-                        lon: 128.9784,
-                        lat: 37.566,
+                    InputGeometryConfig {
+                        name: "Probe".to_string(),
+                        geometry: GeometryConfig::Points(vec![PointConfig::new(128.4, 37.7)]),
                     },
-                ],
-                line: LineConfig {
-                    start: PointConfig {
-                        lon: 127.9784,
-                        lat: 37.566,
+                    InputGeometryConfig {
+                        name: "Line B".to_string(),
+                        geometry: GeometryConfig::LineString(vec![
+                            PointConfig::new(128.0, 37.75),
+                            PointConfig::new(128.6, 37.65),
+                            PointConfig::new(129.2, 37.7),
+                            PointConfig::new(129.6, 37.9),
+                        ]),
                     },
-                    end: PointConfig {
-                        lon: 128.9784,
-                        lat: 37.566,
+                    InputGeometryConfig {
+                        name: "Points".to_string(),
+                        geometry: GeometryConfig::Points(vec![
+                            PointConfig::new(128.3, 37.8),
+                            PointConfig::new(128.6, 37.75),
+                            PointConfig::new(128.9, 37.85),
+                            PointConfig::new(128.5, 38.0),
+                            PointConfig::new(128.7, 38.1),
+                            PointConfig::new(128.15, 38.05),
+                            PointConfig::new(128.35, 38.25),
+                            PointConfig::new(128.95, 38.3),
+                            PointConfig::new(129.2, 38.0),
+                            PointConfig::new(129.1, 37.7),
+                            PointConfig::new(128.75, 37.55),
+                            PointConfig::new(128.45, 37.5),
+                        ]),
                     },
-                },
+                    InputGeometryConfig {
+                        name: "Polygon A".to_string(),
+                        geometry: GeometryConfig::Polygon {
+                            exterior: vec![
+                                PointConfig::new(128.2, 37.0),
+                                PointConfig::new(129.0, 37.0),
+                                PointConfig::new(129.0, 37.4),
+                                PointConfig::new(128.2, 37.4),
+                            ],
+                            interiors: vec![],
+                        },
+                    },
+                    InputGeometryConfig {
+                        name: "Polygon B".to_string(),
+                        geometry: GeometryConfig::Polygon {
+                            exterior: vec![
+                                PointConfig::new(128.7, 37.2),
+                                PointConfig::new(129.4, 37.2),
+                                PointConfig::new(129.4, 37.6),
+                                PointConfig::new(128.7, 37.6),
+                            ],
+                            interiors: vec![],
+                        },
+                    },
+                ],
             },
         }
     }
 }
 
+/// Handles to the editing state shared between the map event handler and the egui window.
+#[derive(Clone)]
+pub struct SharedEditState {
+    /// Holds the input geometries with their vertices in Cartesian map coordinates (Point2).
+    /// This data is kept in sync with the map layer's points and is also used as the source
+    /// to update the line features drawn through those points.
+    pub inputs: Arc<RwLock<Vec<InputGeometry>>>,
+    /// Maps the FeatureId of a draggable point to the input vertex it represents.
+    pub feature_id_to_vertex: Arc<RwLock<HashMap<FeatureId, VertexRef>>>,
+    /// FeatureIds of the line features drawn through each (geometry, ring) of the inputs: one, or
+    /// one per piece while the ring crosses the antimeridian.
+    pub ring_feature_ids: Arc<RwLock<HashMap<(usize, usize), Vec<FeatureId>>>>,
+    /// The point being dragged, or last clicked, which keyboard nudges apply to.
+    pub selected_feature_id: Arc<RwLock<Option<FeatureId>>>,
+    pub snap_settings: Arc<RwLock<SnapSettings>>,
+    pub snap_indicator: Arc<RwLock<Option<SnapResult>>>,
+    /// Parameters of the affine transform algorithms, edited by their sliders and the gizmo.
+    pub affine_params: Arc<RwLock<AffineParams>>,
+    /// Transform origin in the map CRS as last painted by the window, or None while the gizmo is
+    /// hidden.
+    pub gizmo_origin: Arc<RwLock<Option<Point2>>>,
+    /// The gizmo handle being dragged, if any.
+    pub gizmo_handle: Arc<RwLock<Option<GizmoHandle>>>,
+    /// CRS of the input vertices, i.e. the map's projection.
+    pub map_crs: Crs,
+    pub error_log: ErrorLog,
+}
+
+impl SharedEditState {
+    /// Returns the selected point's FeatureId together with the vertex it represents.
+    pub fn selected_vertex(&self) -> Option<(FeatureId, VertexRef)> {
+        let feature_id = (*self.selected_feature_id.read().unwrap())?;
+        let vertex = *self.feature_id_to_vertex.read().unwrap().get(&feature_id)?;
+        Some((feature_id, vertex))
+    }
+
+    /// Selects the point of `vertex`, as if it had been clicked on the map.
+    pub fn select_vertex(&self, vertex: VertexRef) {
+        *self.selected_feature_id.write().unwrap() = self.vertex_feature_id(vertex);
+    }
+
+    /// The point feature that `vertex` is dragged by.
+    pub fn vertex_feature_id(&self, vertex: VertexRef) -> Option<FeatureId> {
+        self.feature_id_to_vertex
+            .read()
+            .unwrap()
+            .iter()
+            .find_map(|(feature_id, v)| (*v == vertex).then_some(*feature_id))
+    }
+
+    pub fn vertex_position(&self, vertex: VertexRef) -> Option<Point2> {
+        self.inputs
+            .read()
+            .unwrap()
+            .get(vertex.geometry)?
+            .rings
+            .get(vertex.ring)?
+            .get(vertex.vertex)
+            .copied()
+    }
+}
+
 #[cfg(target_family = "wasm")]
 #[cfg_attr(target_family = "wasm", wasm_bindgen)]
-pub fn main() {
+pub fn main() -> Result<(), JsValue> {
     console_error_panic_hook::set_once();
-    run(AppConfig::default()); // Pass default config to run
+    // Pass default config to run
+    run(AppConfig::default()).map_err(|e| JsValue::from_str(&e.to_string()))
 }
 
-pub fn run(config: AppConfig) {
-    let projection_for_initial_points = Crs::EPSG3857
-        .get_projection::<GeoPoint2d, Point2>()
-        .expect("must find projection for initial points");
-
-    let initial_points_data: Vec<Point2> = config
+pub fn run(config: AppConfig) -> Result<(), AppError> {
+    let inputs = config
         .geometries
-        .draggable_points
+        .inputs
         .iter()
-        .map(|p_config| {
-            // Convert PointConfig to geo::Point for Disambiguate trait
-            let geo_type_point = geo::Point::new(p_config.lon, p_config.lat);
-            // Disambiguate and then convert to galileo_types::geo::Point for projection
-            let p_geo_disambig = geo_type_point.to_geo2d(); // p_geo is Disambig<geo::Point<f64>, GeoSpace2d>
-            let galileo_geo_point = GeoPoint2d::lonlat(p_geo_disambig.lon(), p_geo_disambig.lat());
-            projection_for_initial_points
-                .project(&galileo_geo_point)
-                .expect("Initial point projection failed")
-        })
-        .collect();
-
-    // Holds the Cartesian coordinates (Point2) of the two draggable points.
-    // This data is kept in sync with the map layer's points and is also used as the source
-    // to update the endpoints of the line feature connecting these points.
-    let shared_points_data = Arc::new(RwLock::new(initial_points_data.clone()));
-
-    // Maps the FeatureId of a draggable point to its original index (0 or 1) in the initial_points_data
-    // and subsequently in shared_points_data. This is used to update the correct point in shared_points_data.
-    let feature_id_to_index_map = Arc::new(RwLock::new(HashMap::<FeatureId, usize>::new()));
-
-    // Stores the FeatureId of the line feature (from vector_layer2).
-    // This ID is used in `handle_drag` to specifically target the line feature for geometry updates
-    // when one of the draggable points (its endpoints) moves.
-    let line_feature_id_arc = Arc::new(RwLock::new(None::<FeatureId>));
-
-    // Pass geometry and view configs to create_map
-    let map_instance = create_map(
-        initial_points_data, // This is already projected Vec<Point2>
-        &config.geometries,  // Pass reference to geometry config
-        &config.map_view,    // Pass reference to view config
-        line_feature_id_arc.clone(),
-    );
-
-    // Populate the feature_id_to_index_map
+        .map(|input_config| InputGeometry::from_config(input_config, &config.map_view.map_crs))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // Pass the projected inputs and view config to create_map
+    let mut map_instance = create_map(&inputs, &config.map_view)?;
+
+    // Maps the FeatureId of each draggable point to the input vertex it was created from. The
+    // point layer holds the vertices in `FlatVertices` order.
+    let mut feature_id_to_vertex = HashMap::<FeatureId, VertexRef>::new();
+    // Maps each (geometry, ring) drawn as a line to the FeatureIds of its line features, which
+    // `move_point` replaces when one of the ring's vertices moves.
+    let mut ring_feature_ids = HashMap::<(usize, usize), Vec<FeatureId>>::new();
     {
-        let mut map_writer = feature_id_to_index_map.write().unwrap();
-        let mut layer_found_and_mapped = false;
-        // Iterate over layers to find the one we want to map IDs from.
-        // This assumes it's the first FeatureLayer of its specific type, or you might need a more robust way to identify it (e.g., layer ID).
+        let vertex_refs = FlatVertices::new(&inputs).refs;
+        let mut point_layer_found = false;
+        // Iterate over layers to find the ones we want to map IDs from.
+        // This assumes each is the first FeatureLayer of its specific type.
         for layer_trait_object in map_instance.layers().iter() {
-            if let Some(feature_layer) = layer_trait_object.as_any().downcast_ref::<FeatureLayer<
-                Point2,
-                Point2,
-                CirclePointSymbol,
-                CartesianSpace2d,
-            >>() {
-                println!("Found target FeatureLayer for ID mapping.");
-                for (original_index, (feature_id, _point_feature)) in
-                    feature_layer.features().iter().enumerate()
+            if let Some(feature_layer) = layer_as_point_feature_layer(layer_trait_object) {
+                if point_layer_found {
+                    continue;
+                }
+                for ((feature_id, _point_feature), vertex) in
+                    feature_layer.features().iter().zip(&vertex_refs)
                 {
-                    map_writer.insert(feature_id, original_index);
-                    println!(
-                        "Mapping actual FeatureId {:?} to original_index {}",
-                        feature_id, original_index
-                    );
+                    feature_id_to_vertex.insert(feature_id, *vertex);
                 }
-                layer_found_and_mapped = true;
-                break; // Found and mapped our layer
+                point_layer_found = true;
             }
         }
-        if !layer_found_and_mapped {
-            eprintln!(
-                "WARNING: Could not find the target FeatureLayer to build the FeatureId-to-index map."
-            );
+        if !point_layer_found {
+            return Err(AppError::PointLayerNotFound);
         }
-    }
 
-    let selected_feature_id_handler = Arc::new(RwLock::new(None::<FeatureId>));
+        // The line layer starts empty, since the number of features of a ring depends on
+        // whether it crosses the antimeridian.
+        let line_layer = map_instance
+            .layers_mut()
+            .iter_mut()
+            .find_map(|layer| layer_as_line_feature_layer_mut(layer.as_mut()))
+            .ok_or(AppError::LineLayerNotFound)?;
+        for (geometry, ring) in contour_ring_refs(&inputs) {
+            let contours = ring_contours(&inputs[geometry], ring, &config.map_view.map_crs)?;
+            let feature_ids = ring_feature_ids.entry((geometry, ring)).or_default();
+            set_ring_contours(line_layer, feature_ids, contours)?;
+        }
+    }
 
-    let handler_shared_points = shared_points_data.clone();
-    let handler_id_map = feature_id_to_index_map.clone();
-    let handler_line_id = line_feature_id_arc.clone();
+    let edit_state = SharedEditState {
+        inputs: Arc::new(RwLock::new(inputs)),
+        feature_id_to_vertex: Arc::new(RwLock::new(feature_id_to_vertex)),
+        ring_feature_ids: Arc::new(RwLock::new(ring_feature_ids)),
+        selected_feature_id: Arc::new(RwLock::new(None::<FeatureId>)),
+        // Snap modes are toggled from the egui window and read by the drag handler, which in turn
+        // publishes the active snap so the window can draw an indicator for it.
+        snap_settings: Arc::new(RwLock::new(SnapSettings::default())),
+        snap_indicator: Arc::new(RwLock::new(None::<SnapResult>)),
+        affine_params: Arc::new(RwLock::new(AffineParams::default())),
+        gizmo_origin: Arc::new(RwLock::new(None)),
+        gizmo_handle: Arc::new(RwLock::new(None)),
+        map_crs: config.map_view.map_crs.clone(),
+        error_log: ErrorLog::default(),
+    };
 
+    let handler_edit_state = edit_state.clone();
     let handler: Box<dyn UserEventHandler> = Box::new(move |ev: &UserEvent, map: &mut Map| {
-        let captured_shared_points = handler_shared_points.clone();
-        let captured_id_map = handler_id_map.clone();
-        let captured_line_id = handler_line_id.clone();
         match ev {
             UserEvent::DragStarted(mouse_button, event) => {
-                handle_drag_started(mouse_button, event, map, &selected_feature_id_handler)
+                // Gizmo handles are drawn over the points, so they are grabbed first.
+                let handle = gizmo_handle_at(map, event, &handler_edit_state);
+                *handler_edit_state.gizmo_handle.write().unwrap() = handle;
+                match handle {
+                    Some(_) => EventPropagation::Consume,
+                    None => match handle_drag_started(
+                        mouse_button,
+                        event,
+                        map,
+                        &handler_edit_state.selected_feature_id,
+                    ) {
+                        Ok(propagation) => propagation,
+                        Err(e) => {
+                            handler_edit_state.error_log.push("starting a drag", e);
+                            EventPropagation::Propagate
+                        }
+                    },
+                }
+            }
+            UserEvent::Drag(_, _, event)
+                if handler_edit_state.gizmo_handle.read().unwrap().is_some() =>
+            {
+                match handle_gizmo_drag(event, map, &handler_edit_state) {
+                    Ok(propagation) => propagation,
+                    Err(e) => {
+                        handler_edit_state
+                            .error_log
+                            .push("dragging the transform gizmo", e);
+                        EventPropagation::Stop
+                    }
+                }
             }
             UserEvent::Drag(mouse_button, delta, event) => {
-                match handle_drag(
-                    mouse_button,
-                    delta,
-                    event,
-                    &selected_feature_id_handler,
-                    map,
-                    &captured_shared_points,
-                    &captured_id_map,
-                    &captured_line_id,
-                ) {
+                match handle_drag(mouse_button, delta, event, map, &handler_edit_state) {
                     Ok(propagation) => propagation,
                     Err(e) => {
-                        eprintln!("An error occurred during drag: {:?}", e);
+                        handler_edit_state.error_log.push("dragging a point", e);
                         EventPropagation::Stop // Default to Stop on error, or choose another suitable propagation.
                     }
                 }
             }
+            UserEvent::DragEnded(_, _) => {
+                *handler_edit_state.snap_indicator.write().unwrap() = None;
+                *handler_edit_state.gizmo_handle.write().unwrap() = None;
+                EventPropagation::Propagate
+            }
+            UserEvent::Click(_, event) => {
+                // Clicking a point selects it for keyboard nudging; clicking elsewhere clears it.
+                let clicked = find_point_feature_at(map, event).unwrap_or_else(|e| {
+                    handler_edit_state.error_log.push("selecting a point", e);
+                    None
+                });
+                *handler_edit_state.selected_feature_id.write().unwrap() = clicked;
+                match clicked {
+                    Some(_) => EventPropagation::Consume,
+                    None => EventPropagation::Propagate,
+                }
+            }
             _ => EventPropagation::Propagate,
         }
     });
     let mut builder = galileo_egui::InitBuilder::new(map_instance);
 
     builder = builder
-        .with_app_builder(move |egui_map_state| Box::new(EguiMapApp::new(egui_map_state)))
+        .with_app_builder(move |egui_map_state| {
+            Box::new(EguiMapApp::new(
+                egui_map_state,
+                edit_state.clone(),
+                config.map_view.working_crs,
+            ))
+        })
         .with_handlers(vec![handler]);
 
     #[cfg(target_family = "wasm")]
@@ -223,145 +402,365 @@ pub fn run(config: AppConfig) {
         });
     }
 
-    builder.init().expect("failed to initialize");
+    builder
+        .init()
+        .map_err(|e| AppError::InitFailed(format!("{:?}", e)))
+}
+
+// The transform gizmo handle under the pointer, if the gizmo is shown.
+fn gizmo_handle_at(
+    map: &Map,
+    event: &galileo::control::MouseEvent,
+    edit_state: &SharedEditState,
+) -> Option<GizmoHandle> {
+    let origin = (*edit_state.gizmo_origin.read().unwrap())?;
+    let position = map.view().screen_to_map(event.screen_pointer_position)?;
+    let resolution = map.view().resolution();
+    GizmoLayout::new(
+        origin,
+        &edit_state.affine_params.read().unwrap(),
+        resolution,
+    )
+    .handle_at(&position, resolution)
 }
 
-#[derive(Debug)]
-pub enum DragError {
-    ScreenToMapConversionFailed,
-    SelectedFeatureIdMissing,
-    // Point update related errors
-    PointFeatureNotFoundInLayer(FeatureId),
-    FailedToUpdateSharedPointIndex(FeatureId, usize), // feature_id, index
-    FailedToFindSharedPointId(FeatureId),
-    // Line update related errors
-    LineIdUnavailable,
-    InsufficientSharedPointsForLine,
-    ProjectionUnavailable,
-    UnprojectionFailed,
-    LineFeatureNotFoundInLayer(FeatureId),
+fn handle_gizmo_drag(
+    event: &galileo::control::MouseEvent,
+    map: &mut Map,
+    edit_state: &SharedEditState,
+) -> Result<EventPropagation, DragError> {
+    let (Some(handle), Some(origin)) = (
+        *edit_state.gizmo_handle.read().unwrap(),
+        *edit_state.gizmo_origin.read().unwrap(),
+    ) else {
+        return Ok(EventPropagation::Propagate);
+    };
+    let position = map
+        .view()
+        .screen_to_map(event.screen_pointer_position)
+        .ok_or(DragError::ScreenToMapConversionFailed)?;
+    let resolution = map.view().resolution();
+
+    let mut params = edit_state.affine_params.write().unwrap();
+    let layout = GizmoLayout::new(origin, &params, resolution);
+    gizmo::drag_handle(
+        handle,
+        &position,
+        &layout,
+        &mut params,
+        resolution,
+        &edit_state.map_crs,
+    );
+    map.redraw();
+    Ok(EventPropagation::Consume)
 }
 
 fn handle_drag(
     _mouse_button: &galileo::control::MouseButton,
     _delta: &galileo_types::cartesian::Vector2<f64>,
     event: &galileo::control::MouseEvent,
-    feature_id_arc: &Arc<RwLock<Option<FeatureId>>>,
     map: &mut Map,
-    shared_points: &Arc<RwLock<Vec<Point2>>>,
-    id_to_index_map: &Arc<RwLock<HashMap<FeatureId, usize>>>,
-    line_id_arc: &Arc<RwLock<Option<FeatureId>>>,
+    edit_state: &SharedEditState,
 ) -> Result<EventPropagation, DragError> {
-    let opt_feature_id_to_drag = *feature_id_arc.read().unwrap();
+    let opt_feature_id_to_drag = *edit_state.selected_feature_id.read().unwrap();
     if let Some(feature_id_to_drag) = opt_feature_id_to_drag {
-        let new_feature_position = map
+        let pointer_position = map
             .view()
             .screen_to_map(event.screen_pointer_position)
             .ok_or(DragError::ScreenToMapConversionFailed)?;
 
-        let mut needs_redraw = false;
-        let mut point_updated_in_layer = false;
-
-        for layer_trait_object in map.layers_mut().iter_mut() {
-            if let Some(feature_layer) = layer_trait_object
-                .as_any_mut()
-                .downcast_mut::<FeatureLayer<Point2, Point2, CirclePointSymbol, CartesianSpace2d>>()
-            {
-                if feature_layer
-                    .features_mut()
-                    .get_mut(feature_id_to_drag)
-                    .is_some()
-                {
-                    let point_to_update = feature_layer
-                        .features_mut()
-                        .get_mut(feature_id_to_drag)
-                        .unwrap();
-                    *point_to_update = new_feature_position;
-                    feature_layer.update_feature(feature_id_to_drag);
-                    point_updated_in_layer = true;
-
-                    let id_map_reader = id_to_index_map.read().unwrap();
-                    if let Some(index) = id_map_reader.get(&feature_id_to_drag) {
-                        let mut shared_points_writer = shared_points.write().unwrap();
-                        if *index < shared_points_writer.len() {
-                            shared_points_writer[*index] = new_feature_position;
-                        } else {
-                            return Err(DragError::FailedToUpdateSharedPointIndex(
-                                feature_id_to_drag,
-                                *index,
-                            ));
-                        }
-                    } else {
-                        return Err(DragError::FailedToFindSharedPointId(feature_id_to_drag));
-                    }
+        let dragged_vertex = *edit_state
+            .feature_id_to_vertex
+            .read()
+            .unwrap()
+            .get(&feature_id_to_drag)
+            .ok_or(DragError::FailedToFindSharedPointId(feature_id_to_drag))?;
+
+        let snap = {
+            let flat_vertices = FlatVertices::new(&edit_state.inputs.read().unwrap());
+            let dragged_index = flat_vertices.index_of(dragged_vertex).ok_or(
+                DragError::FailedToUpdateSharedPointIndex(feature_id_to_drag, dragged_vertex),
+            )?;
+            snapping::snap_position(
+                pointer_position,
+                dragged_index,
+                &flat_vertices.points,
+                &flat_vertices.segments,
+                &edit_state.snap_settings.read().unwrap(),
+                map.view().resolution(),
+                &edit_state.map_crs,
+            )
+        };
+        *edit_state.snap_indicator.write().unwrap() = snap;
+        let new_feature_position = snap.map_or(pointer_position, |snap| snap.position);
+
+        move_point(map, edit_state, feature_id_to_drag, new_feature_position)?;
+        Ok(EventPropagation::Consume)
+    } else {
+        Ok(EventPropagation::Propagate)
+    }
+}
 
-                    needs_redraw = true;
-                    break;
-                }
+/// Moves a draggable point to `new_position` (map CRS), keeping the input geometry and the line
+/// feature of its ring in sync with it. Used by mouse drags as well as keyboard and numeric
+/// edits.
+pub fn move_point(
+    map: &mut Map,
+    edit_state: &SharedEditState,
+    feature_id: FeatureId,
+    new_position: Point2,
+) -> Result<(), DragError> {
+    let vertex = *edit_state
+        .feature_id_to_vertex
+        .read()
+        .unwrap()
+        .get(&feature_id)
+        .ok_or(DragError::FailedToFindSharedPointId(feature_id))?;
+    let new_position = antimeridian::normalize_map_position(&new_position, &edit_state.map_crs)?;
+
+    let mut point_updated_in_layer = false;
+    for layer_trait_object in map.layers_mut().iter_mut() {
+        if let Some(feature_layer) = layer_trait_object.as_any_mut().downcast_mut::<FeatureLayer<
+            Point2,
+            Point2,
+            CirclePointSymbol,
+            CartesianSpace2d,
+        >>() {
+            if let Some(point_to_update) = feature_layer.features_mut().get_mut(feature_id) {
+                *point_to_update = new_position;
+                feature_layer.update_feature(feature_id);
+                point_updated_in_layer = true;
+                break;
             }
         }
+    }
 
-        if !point_updated_in_layer {
-            return Err(DragError::PointFeatureNotFoundInLayer(feature_id_to_drag));
-        }
+    if !point_updated_in_layer {
+        return Err(DragError::PointFeatureNotFoundInLayer(feature_id));
+    }
 
-        if needs_redraw {
-            let opt_line_id_to_update = *line_id_arc.read().unwrap();
-            let line_id_to_update = opt_line_id_to_update.ok_or(DragError::LineIdUnavailable)?;
+    let mut inputs = edit_state.inputs.write().unwrap();
+    let vertex_to_update = inputs
+        .get_mut(vertex.geometry)
+        .and_then(|geometry| geometry.rings.get_mut(vertex.ring))
+        .and_then(|ring| ring.get_mut(vertex.vertex))
+        .ok_or(DragError::FailedToUpdateSharedPointIndex(
+            feature_id, vertex,
+        ))?;
+    *vertex_to_update = new_position;
+
+    let geometry = &inputs[vertex.geometry];
+    if geometry.has_contours() {
+        let mut ring_feature_ids = edit_state.ring_feature_ids.write().unwrap();
+        let feature_ids = ring_feature_ids
+            .get_mut(&(vertex.geometry, vertex.ring))
+            .ok_or(DragError::LineIdUnavailable)?;
+        let contours = ring_contours(geometry, vertex.ring, &edit_state.map_crs)?;
+
+        let line_layer = map
+            .layers_mut()
+            .iter_mut()
+            .find_map(|layer| layer_as_line_feature_layer_mut(layer.as_mut()))
+            .ok_or(DragError::LineLayerNotFound)?;
+        set_ring_contours(line_layer, feature_ids, contours)?;
+    }
 
-            let current_cartesian_points = shared_points.read().unwrap();
-            if current_cartesian_points.len() < 2 {
-                return Err(DragError::InsufficientSharedPointsForLine);
-            }
-            let p1_cartesian = current_cartesian_points[0];
-            let p2_cartesian = current_cartesian_points[1];
-
-            let p1_geo_proj = unproject_cartesian_point_to_geo(&p1_cartesian)?;
-            let p2_geo_proj = unproject_cartesian_point_to_geo(&p2_cartesian)?;
-
-            let p1_geo_coord = geo::coord!(x: p1_geo_proj.lon(), y: p1_geo_proj.lat());
-            let p2_geo_coord = geo::coord!(x: p2_geo_proj.lon(), y: p2_geo_proj.lat());
-
-            let new_line_contour_data = Contour::new(vec![p1_geo_coord, p2_geo_coord], false);
-
-            let mut line_layer_updated_successfully = false;
-            for layer_trait_object_mut in map.layers_mut().iter_mut() {
-                if let Some(line_feature_layer) = layer_trait_object_mut
-                    .as_any_mut()
-                    .downcast_mut::<FeatureLayer<
-                        geo::Coord<f64>,
-                        Contour<geo::Coord<f64>>,
-                        SimpleContourSymbol,
-                        GeoSpace2d,
-                    >>()
-                {
-                    if let Some(line_to_update) =
-                        line_feature_layer.features_mut().get_mut(line_id_to_update)
-                    {
-                        *line_to_update = new_line_contour_data.clone();
-                        line_feature_layer.update_feature(line_id_to_update);
-                        line_layer_updated_successfully = true;
-                        break;
-                    }
-                }
+    map.redraw();
+    Ok(())
+}
+
+/// The WGS84 line features drawn through one ring of an input geometry. A ring that crosses the
+/// antimeridian is cut into open pieces on either side of it, since a single contour would run the
+/// long way round the world.
+fn ring_contours(
+    geometry: &InputGeometry,
+    ring: usize,
+    crs: &Crs,
+) -> Result<Vec<Contour<geo::Coord<f64>>>, DragError> {
+    let mut coords = geometry.ring_to_lonlat(ring, crs)?;
+    let closed = geometry.rings_are_closed();
+    if closed {
+        coords.extend(coords.first().copied());
+    }
+    if !antimeridian::crosses_antimeridian(&coords) {
+        if closed {
+            coords.pop();
+        }
+        return Ok(vec![Contour::new(coords, closed)]);
+    }
+    Ok(antimeridian::split_at_antimeridian(&coords)
+        .into_iter()
+        .map(|piece| Contour::new(piece, false))
+        .collect())
+}
+
+/// Replaces the line features in `feature_ids` with `contours`, reusing features where it can and
+/// adding or removing the rest.
+fn set_ring_contours(
+    line_layer: &mut FeatureLayer<
+        geo::Coord<f64>,
+        Contour<geo::Coord<f64>>,
+        SimpleContourSymbol,
+        GeoSpace2d,
+    >,
+    feature_ids: &mut Vec<FeatureId>,
+    contours: Vec<Contour<geo::Coord<f64>>>,
+) -> Result<(), DragError> {
+    for surplus in feature_ids.split_off(contours.len().min(feature_ids.len())) {
+        line_layer.features_mut().remove(surplus);
+    }
+    for (i, contour) in contours.into_iter().enumerate() {
+        match feature_ids.get(i) {
+            Some(&feature_id) => {
+                let feature = line_layer
+                    .features_mut()
+                    .get_mut(feature_id)
+                    .ok_or(DragError::LineFeatureNotFoundInLayer(feature_id))?;
+                *feature = contour;
             }
+            None => feature_ids.push(line_layer.features_mut().add(contour)),
+        }
+    }
+    line_layer.update_all_features();
+    Ok(())
+}
 
-            if !line_layer_updated_successfully {
-                return Err(DragError::LineFeatureNotFoundInLayer(line_id_to_update));
+/// Appends `input` to the inputs and creates its point and line features, so it can be dragged like
+/// the configured inputs. Returns the index of the new input.
+pub fn add_input(
+    map: &mut Map,
+    edit_state: &SharedEditState,
+    input: InputGeometry,
+) -> Result<usize, AppError> {
+    let geometry_index = edit_state.inputs.read().unwrap().len();
+    let contours = if input.has_contours() {
+        (0..input.rings.len())
+            .map(|ring| ring_contours(&input, ring, &edit_state.map_crs))
+            .collect::<Result<Vec<_>, _>>()?
+    } else {
+        Vec::new()
+    };
+
+    let point_layer = map
+        .layers_mut()
+        .iter_mut()
+        .find_map(|layer| layer_as_point_feature_layer_mut(layer.as_mut()))
+        .ok_or(AppError::PointLayerNotFound)?;
+    {
+        let mut feature_id_to_vertex = edit_state.feature_id_to_vertex.write().unwrap();
+        for (ring, points) in input.rings.iter().enumerate() {
+            for (vertex, point) in points.iter().enumerate() {
+                let feature_id = point_layer.features_mut().add(*point);
+                feature_id_to_vertex.insert(
+                    feature_id,
+                    VertexRef {
+                        geometry: geometry_index,
+                        ring,
+                        vertex,
+                    },
+                );
             }
+        }
+    }
+    point_layer.update_all_features();
+
+    if !contours.is_empty() {
+        let line_layer = map
+            .layers_mut()
+            .iter_mut()
+            .find_map(|layer| layer_as_line_feature_layer_mut(layer.as_mut()))
+            .ok_or(AppError::LineLayerNotFound)?;
+        let mut ring_feature_ids = edit_state.ring_feature_ids.write().unwrap();
+        for (ring, contours) in contours.into_iter().enumerate() {
+            let feature_ids = ring_feature_ids.entry((geometry_index, ring)).or_default();
+            set_ring_contours(line_layer, feature_ids, contours)?;
+        }
+    }
+
+    edit_state.inputs.write().unwrap().push(input);
+    map.redraw();
+    Ok(geometry_index)
+}
+
+/// Removes the last input together with its point and line features. Only the last input can be
+/// removed, since the vertex references of every later input would shift otherwise.
+pub fn remove_last_input(map: &mut Map, edit_state: &SharedEditState) -> Result<(), AppError> {
+    let Some(geometry_index) = edit_state.inputs.read().unwrap().len().checked_sub(1) else {
+        return Ok(());
+    };
 
-            map.redraw();
-            Ok(EventPropagation::Consume)
-        } else {
-            Ok(EventPropagation::Stop)
+    let point_layer = map
+        .layers_mut()
+        .iter_mut()
+        .find_map(|layer| layer_as_point_feature_layer_mut(layer.as_mut()))
+        .ok_or(AppError::PointLayerNotFound)?;
+    {
+        let mut feature_id_to_vertex = edit_state.feature_id_to_vertex.write().unwrap();
+        let removed: Vec<FeatureId> = feature_id_to_vertex
+            .iter()
+            .filter(|(_, vertex)| vertex.geometry == geometry_index)
+            .map(|(feature_id, _)| *feature_id)
+            .collect();
+        for feature_id in removed {
+            point_layer.features_mut().remove(feature_id);
+            feature_id_to_vertex.remove(&feature_id);
         }
-    } else {
-        Ok(EventPropagation::Propagate)
     }
+    point_layer.update_all_features();
+
+    let line_layer = map
+        .layers_mut()
+        .iter_mut()
+        .find_map(|layer| layer_as_line_feature_layer_mut(layer.as_mut()))
+        .ok_or(AppError::LineLayerNotFound)?;
+    {
+        let mut ring_feature_ids = edit_state.ring_feature_ids.write().unwrap();
+        ring_feature_ids.retain(|(geometry, _), feature_ids| {
+            let keep = *geometry != geometry_index;
+            if !keep {
+                for feature_id in feature_ids.iter() {
+                    line_layer.features_mut().remove(*feature_id);
+                }
+            }
+            keep
+        });
+    }
+    line_layer.update_all_features();
+
+    let mut selected = edit_state.selected_feature_id.write().unwrap();
+    if selected.is_some_and(|id| {
+        !edit_state
+            .feature_id_to_vertex
+            .read()
+            .unwrap()
+            .contains_key(&id)
+    }) {
+        *selected = None;
+    }
+    drop(selected);
+
+    edit_state.inputs.write().unwrap().pop();
+    map.redraw();
+    Ok(())
+}
+
+/// The (geometry, ring) pairs that get a line feature, in line layer order.
+fn contour_ring_refs(inputs: &[InputGeometry]) -> Vec<(usize, usize)> {
+    inputs
+        .iter()
+        .enumerate()
+        .filter(|(_, geometry)| geometry.has_contours())
+        .flat_map(|(geometry_index, geometry)| {
+            (0..geometry.rings.len()).map(move |ring_index| (geometry_index, ring_index))
+        })
+        .collect()
 }
 
-fn unproject_cartesian_point_to_geo(cartesian_point: &Point2) -> Result<GeoPoint2d, DragError> {
-    let projector = Crs::EPSG3857
+pub fn unproject_cartesian_point_to_geo(
+    cartesian_point: &Point2,
+    crs: &Crs,
+) -> Result<GeoPoint2d, DragError> {
+    let projector = crs
         .get_projection::<GeoPoint2d, Point2>()
         .ok_or(DragError::ProjectionUnavailable)?;
     projector
@@ -369,18 +768,42 @@ fn unproject_cartesian_point_to_geo(cartesian_point: &Point2) -> Result<GeoPoint
         .ok_or(DragError::UnprojectionFailed)
 }
 
+pub fn project_geo_point_to_cartesian(
+    geo_point: &GeoPoint2d,
+    crs: &Crs,
+) -> Result<Point2, DragError> {
+    let projector = crs
+        .get_projection::<GeoPoint2d, Point2>()
+        .ok_or(DragError::ProjectionUnavailable)?;
+    projector
+        .project(geo_point)
+        .ok_or(DragError::ProjectionFailed)
+}
+
 fn handle_drag_started(
-    mouse_button: &galileo::control::MouseButton,
+    _mouse_button: &galileo::control::MouseButton,
     event: &galileo::control::MouseEvent,
     map: &mut galileo::Map,
     feature_id_arc: &Arc<RwLock<Option<FeatureId>>>,
-) -> EventPropagation {
-    let Some(position) = map.view().screen_to_map(event.screen_pointer_position) else {
-        eprintln!(
-            "Failed to convert screen position to map Cartesian coordinates for drag_started"
-        );
-        return EventPropagation::Stop;
-    };
+) -> Result<EventPropagation, DragError> {
+    // A drag that starts away from every point pans the map, so it must not keep moving the
+    // previously selected point, even if the lookup fails.
+    let found_feature_id = find_point_feature_at(map, event);
+    *feature_id_arc.write().unwrap() = found_feature_id.as_ref().ok().copied().flatten();
+    Ok(match found_feature_id? {
+        Some(_) => EventPropagation::Consume,
+        None => EventPropagation::Propagate,
+    })
+}
+
+fn find_point_feature_at(
+    map: &galileo::Map,
+    event: &galileo::control::MouseEvent,
+) -> Result<Option<FeatureId>, DragError> {
+    let position = map
+        .view()
+        .screen_to_map(event.screen_pointer_position)
+        .ok_or(DragError::ScreenToMapConversionFailed)?;
 
     let resolution = map.view().resolution();
 
@@ -390,13 +813,11 @@ fn handle_drag_started(
                 .get_features_at(&position, resolution * 7.0)
                 .next()
             {
-                let mut feature_id_writer = (*feature_id_arc).write().unwrap();
-                *feature_id_writer = Some(found_feature_id);
-                return EventPropagation::Consume;
+                return Ok(Some(found_feature_id));
             }
         }
     }
-    EventPropagation::Propagate
+    Ok(None)
 }
 
 fn layer_as_point_feature_layer(
@@ -407,6 +828,27 @@ fn layer_as_point_feature_layer(
         .downcast_ref::<FeatureLayer<Point2, Point2, CirclePointSymbol, CartesianSpace2d>>()
 }
 
+fn layer_as_point_feature_layer_mut(
+    layer: &mut dyn Layer,
+) -> Option<&mut FeatureLayer<Point2, Point2, CirclePointSymbol, CartesianSpace2d>> {
+    layer
+        .as_any_mut()
+        .downcast_mut::<FeatureLayer<Point2, Point2, CirclePointSymbol, CartesianSpace2d>>()
+}
+
+fn layer_as_line_feature_layer_mut(
+    layer: &mut dyn Layer,
+) -> Option<
+    &mut FeatureLayer<geo::Coord<f64>, Contour<geo::Coord<f64>>, SimpleContourSymbol, GeoSpace2d>,
+> {
+    layer.as_any_mut().downcast_mut::<FeatureLayer<
+        geo::Coord<f64>,
+        Contour<geo::Coord<f64>>,
+        SimpleContourSymbol,
+        GeoSpace2d,
+    >>()
+}
+
 fn get_default_circle_point_style() -> CirclePointSymbol {
     CirclePointSymbol {
         color: Color::GREEN,
@@ -421,49 +863,43 @@ fn get_default_line_contour_style() -> SimpleContourSymbol {
     }
 }
 
-fn create_map(
-    initial_points: Vec<Point2>,
-    geometries: &MapGeometryConfig,
-    map_view: &MapViewConfig,
-    line_feature_id_arc: Arc<RwLock<Option<FeatureId>>>,
-) -> Map {
-    let layer = RasterTileLayerBuilder::new_osm()
-        .with_file_cache_checked(".tile_cache")
-        .build()
-        .expect("failed to create layer");
-
+fn create_map(inputs: &[InputGeometry], map_view: &MapViewConfig) -> Result<Map, AppError> {
     let vector_layer: FeatureLayer<Point2, Point2, _, CartesianSpace2d> = FeatureLayer::new(
-        initial_points,
+        FlatVertices::new(inputs).points,
         get_default_circle_point_style(),
-        Crs::EPSG3857,
+        map_view.map_crs.clone(),
     );
 
-    let line_data = vec![Contour::new(
-        vec![
-            geo::coord!(x: geometries.line.start.lon, y: geometries.line.start.lat),
-            geo::coord!(x: geometries.line.end.lon, y: geometries.line.end.lat),
-        ],
-        false,
-    )];
-
+    // Filled in by `run` through `set_ring_contours`.
     let vector_layer2: FeatureLayer<geo::Coord<f64>, Contour<geo::Coord<f64>>, _, GeoSpace2d> =
-        FeatureLayer::new(line_data, get_default_line_contour_style(), Crs::WGS84);
+        FeatureLayer::new(Vec::new(), get_default_line_contour_style(), Crs::WGS84);
 
-    {
-        let mut line_id_writer = line_feature_id_arc.write().unwrap();
-        if let Some((id, _)) = vector_layer2.features().iter().next() {
-            *line_id_writer = Some(id);
-            println!("Stored line FeatureId for vector_layer2: {:?}", id);
-        } else {
-            eprintln!("ERROR: vector_layer2 has no features to get ID from.");
-        }
-    }
-
-    MapBuilder::default()
+    let mut builder = MapBuilder::default()
         .with_latlon(map_view.center_lat, map_view.center_lon)
-        .with_z_level(map_view.zoom)
-        .with_layer(layer)
+        .with_z_level(map_view.zoom);
+    if map_view.map_crs == Crs::EPSG3857 {
+        let layer = RasterTileLayerBuilder::new_osm()
+            .with_file_cache_checked(".tile_cache")
+            .build()
+            .map_err(|e| AppError::TileLayerCreationFailed(format!("{:?}", e)))?;
+        builder = builder.with_layer(layer);
+    }
+    let mut map = builder
         .with_layer(vector_layer2)
         .with_layer(vector_layer)
-        .build()
+        .build();
+
+    if map_view.map_crs != Crs::EPSG3857 {
+        // The builder always creates a web mercator view, so swap in one with the configured
+        // projection at the same position and resolution.
+        let position = GeoPoint2d::latlon(map_view.center_lat, map_view.center_lon);
+        let resolution = map.view().resolution();
+        map.set_view(MapView::new_with_crs(
+            &position,
+            resolution,
+            map_view.map_crs.clone(),
+        ));
+    }
+
+    Ok(map)
 }
@@ -1,4 +1,7 @@
 #[cfg(not(target_arch = "wasm32"))]
 fn main() {
-    geo_doc_examples::run(geo_doc_examples::AppConfig::default());
+    if let Err(e) = geo_doc_examples::run(geo_doc_examples::AppConfig::default()) {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
 }
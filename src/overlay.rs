@@ -0,0 +1,439 @@
+//! Shapes painted by egui on top of the map widget, for indicators that are not part of the map
+//! data itself.
+
+use std::borrow::Cow;
+use std::sync::Arc;
+
+use egui::{Color32, Painter, Pos2, Rect, Stroke};
+use galileo_types::cartesian::{CartesianPoint2d, Point2};
+use galileo_types::geo::impls::GeoPoint2d;
+use galileo_types::geo::{Crs, NewGeoPoint};
+use geo::Coord;
+
+use crate::project_geo_point_to_cartesian;
+
+/// A shape in WGS84 lon/lat that an algorithm wants drawn over the map.
+#[derive(Debug, Clone)]
+pub enum OverlayShape {
+    /// A point result, with an optional label drawn next to it.
+    Marker {
+        position: Coord<f64>,
+        label: Option<String>,
+    },
+    /// A closed ring drawn with a dashed outline, used for bounding rectangles.
+    DashedRing(Vec<Coord<f64>>),
+    /// A solid polyline with a dot on every vertex, so vertex counts can be compared by eye.
+    LineString(geo::LineString<f64>),
+    /// A thin dashed line connecting an input to a result, e.g. a probe to its closest point.
+    Connector { start: Coord<f64>, end: Coord<f64> },
+    /// A polygon drawn with a translucent fill and a solid outline. Holes are left unfilled.
+    Polygon(geo::Polygon<f64>),
+    /// A polyline with an arrowhead halfway along every segment, to show which way it runs.
+    DirectedLine(geo::LineString<f64>),
+    /// A compass rose at a point with an arrow along a bearing in degrees clockwise from north. It
+    /// is drawn at a fixed screen size, turned to match where north is on the map.
+    Compass { position: Coord<f64>, bearing: f64 },
+    /// Small dots without outlines or labels, for point sets too large for markers. Shared, so an
+    /// algorithm can hand out the same large set every frame without copying it.
+    PointCloud(Arc<[Coord<f64>]>),
+    /// Thin unconnected segments, e.g. the edges of a triangulation.
+    Segments(Vec<geo::Line<f64>>),
+}
+
+#[derive(Debug, Clone)]
+pub struct Overlay {
+    pub shape: OverlayShape,
+    pub color: Color32,
+}
+
+impl Overlay {
+    pub fn marker(position: Coord<f64>, color: Color32) -> Self {
+        Overlay {
+            shape: OverlayShape::Marker {
+                position,
+                label: None,
+            },
+            color,
+        }
+    }
+
+    pub fn labeled_marker(position: Coord<f64>, label: impl Into<String>, color: Color32) -> Self {
+        Overlay {
+            shape: OverlayShape::Marker {
+                position,
+                label: Some(label.into()),
+            },
+            color,
+        }
+    }
+
+    pub fn polygon(polygon: geo::Polygon<f64>, color: Color32) -> Self {
+        Overlay {
+            shape: OverlayShape::Polygon(polygon),
+            color,
+        }
+    }
+
+    pub fn line_string(line: geo::LineString<f64>, color: Color32) -> Self {
+        Overlay {
+            shape: OverlayShape::LineString(line),
+            color,
+        }
+    }
+
+    pub fn connector(start: Coord<f64>, end: Coord<f64>, color: Color32) -> Self {
+        Overlay {
+            shape: OverlayShape::Connector { start, end },
+            color,
+        }
+    }
+
+    pub fn directed_line(line: geo::LineString<f64>, color: Color32) -> Self {
+        Overlay {
+            shape: OverlayShape::DirectedLine(line),
+            color,
+        }
+    }
+
+    pub fn compass(position: Coord<f64>, bearing: f64, color: Color32) -> Self {
+        Overlay {
+            shape: OverlayShape::Compass { position, bearing },
+            color,
+        }
+    }
+
+    pub fn point_cloud(points: impl Into<Arc<[Coord<f64>]>>, color: Color32) -> Self {
+        Overlay {
+            shape: OverlayShape::PointCloud(points.into()),
+            color,
+        }
+    }
+
+    pub fn segments(segments: Vec<geo::Line<f64>>, color: Color32) -> Self {
+        Overlay {
+            shape: OverlayShape::Segments(segments),
+            color,
+        }
+    }
+
+    pub fn dashed_ring(ring: Vec<Coord<f64>>, color: Color32) -> Self {
+        Overlay {
+            shape: OverlayShape::DashedRing(ring),
+            color,
+        }
+    }
+}
+
+/// Converts positions in the map CRS into egui screen positions for the current map view.
+///
+/// The view only exposes `screen_to_map`, so the transform is recovered by probing it at three
+/// screen pixels and inverting the resulting affine map. Galileo screen pixels are physical
+/// pixels relative to the top-left corner of the map widget.
+pub struct ScreenTransform {
+    origin: Point2,
+    inverse: [[f64; 2]; 2],
+    rect: Rect,
+    pixels_per_point: f32,
+}
+
+impl ScreenTransform {
+    pub fn new(
+        rect: Rect,
+        pixels_per_point: f32,
+        screen_to_map: impl Fn(Point2) -> Option<Point2>,
+    ) -> Option<Self> {
+        let origin = screen_to_map(Point2::new(0.0, 0.0))?;
+        let x_axis = screen_to_map(Point2::new(1.0, 0.0))?;
+        let y_axis = screen_to_map(Point2::new(0.0, 1.0))?;
+
+        let (a, c) = (x_axis.x() - origin.x(), x_axis.y() - origin.y());
+        let (b, d) = (y_axis.x() - origin.x(), y_axis.y() - origin.y());
+        let determinant = a * d - b * c;
+        if determinant == 0.0 || !determinant.is_finite() {
+            return None;
+        }
+
+        Some(ScreenTransform {
+            origin,
+            inverse: [
+                [d / determinant, -b / determinant],
+                [-c / determinant, a / determinant],
+            ],
+            rect,
+            pixels_per_point,
+        })
+    }
+
+    pub fn map_to_screen(&self, position: &Point2) -> Pos2 {
+        let dx = position.x() - self.origin.x();
+        let dy = position.y() - self.origin.y();
+        let px = self.inverse[0][0] * dx + self.inverse[0][1] * dy;
+        let py = self.inverse[1][0] * dx + self.inverse[1][1] * dy;
+        self.rect.min + egui::vec2(px as f32, py as f32) / self.pixels_per_point
+    }
+}
+
+/// Draws a hollow square marker centered at `position`.
+pub fn paint_square_marker(painter: &Painter, position: Pos2, half_size: f32, color: Color32) {
+    painter.rect_stroke(
+        Rect::from_center_size(position, egui::vec2(half_size * 2.0, half_size * 2.0)),
+        0.0,
+        Stroke::new(2.0, color),
+        egui::StrokeKind::Middle,
+    );
+}
+
+/// Draws an algorithm overlay. Coordinates that cannot be projected into `map_crs` are skipped.
+///
+/// Results computed from inputs that cross the antimeridian can have longitudes past ±180°. Such
+/// overlays are drawn a second time shifted by 360°, so the part beyond the edge of the world also
+/// shows up on the other side of it.
+pub fn paint_overlay(
+    painter: &Painter,
+    transform: &ScreenTransform,
+    map_crs: &Crs,
+    overlay: &Overlay,
+) {
+    paint_overlay_shifted(painter, transform, map_crs, overlay, 0.0);
+    let (min_lon, max_lon) = longitude_range(&overlay.shape);
+    if max_lon > 180.0 {
+        paint_overlay_shifted(painter, transform, map_crs, overlay, -360.0);
+    }
+    if min_lon < -180.0 {
+        paint_overlay_shifted(painter, transform, map_crs, overlay, 360.0);
+    }
+}
+
+fn longitude_range(shape: &OverlayShape) -> (f64, f64) {
+    use geo::CoordsIter;
+
+    let coords: Cow<[Coord<f64>]> = match shape {
+        OverlayShape::Marker { position, .. } | OverlayShape::Compass { position, .. } => {
+            Cow::Owned(vec![*position])
+        }
+        OverlayShape::DashedRing(ring) => Cow::Borrowed(ring),
+        OverlayShape::PointCloud(points) => Cow::Borrowed(points),
+        OverlayShape::LineString(line) | OverlayShape::DirectedLine(line) => Cow::Borrowed(&line.0),
+        OverlayShape::Connector { start, end } => Cow::Owned(vec![*start, *end]),
+        OverlayShape::Polygon(polygon) => Cow::Owned(polygon.exterior_coords_iter().collect()),
+        OverlayShape::Segments(segments) => Cow::Owned(
+            segments
+                .iter()
+                .flat_map(|segment| [segment.start, segment.end])
+                .collect(),
+        ),
+    };
+    coords
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), coord| {
+            (min.min(coord.x), max.max(coord.x))
+        })
+}
+
+/// Draws an overlay with every longitude moved by `lon_offset` degrees.
+fn paint_overlay_shifted(
+    painter: &Painter,
+    transform: &ScreenTransform,
+    map_crs: &Crs,
+    overlay: &Overlay,
+    lon_offset: f64,
+) {
+    let to_screen = |coord: &Coord<f64>| {
+        project_geo_point_to_cartesian(&GeoPoint2d::lonlat(coord.x + lon_offset, coord.y), map_crs)
+            .ok()
+            .map(|point| transform.map_to_screen(&point))
+    };
+    let stroke = Stroke::new(2.0, overlay.color);
+
+    match &overlay.shape {
+        OverlayShape::Marker { position, label } => {
+            let Some(position) = to_screen(position) else {
+                return;
+            };
+            painter.circle(
+                position,
+                5.0,
+                overlay.color,
+                Stroke::new(1.5, Color32::BLACK),
+            );
+            if let Some(label) = label {
+                painter.text(
+                    position + egui::vec2(8.0, -8.0),
+                    egui::Align2::LEFT_BOTTOM,
+                    label,
+                    egui::FontId::proportional(12.0),
+                    overlay.color,
+                );
+            }
+        }
+        OverlayShape::DashedRing(ring) => {
+            let mut points: Vec<Pos2> = ring.iter().filter_map(to_screen).collect();
+            if let (Some(first), Some(last)) = (points.first().copied(), points.last()) {
+                if first != *last {
+                    points.push(first);
+                }
+            }
+            painter.extend(egui::Shape::dashed_line(&points, stroke, 8.0, 5.0));
+        }
+        OverlayShape::LineString(line) => {
+            let points: Vec<Pos2> = line.coords().filter_map(to_screen).collect();
+            painter.add(egui::Shape::line(points.clone(), stroke));
+            for point in points {
+                painter.circle_filled(point, 3.0, overlay.color);
+            }
+        }
+        OverlayShape::Connector { start, end } => {
+            let (Some(start), Some(end)) = (to_screen(start), to_screen(end)) else {
+                return;
+            };
+            painter.extend(egui::Shape::dashed_line(
+                &[start, end],
+                Stroke::new(1.5, overlay.color),
+                6.0,
+                4.0,
+            ));
+        }
+        OverlayShape::Polygon(polygon) => {
+            let screen_ring = |ring: &geo::LineString<f64>| -> Option<Vec<Pos2>> {
+                ring.coords().map(to_screen).collect()
+            };
+            let Some(exterior) = screen_ring(polygon.exterior()) else {
+                return;
+            };
+            let Some(interiors) = polygon
+                .interiors()
+                .iter()
+                .map(screen_ring)
+                .collect::<Option<Vec<_>>>()
+            else {
+                return;
+            };
+            paint_polygon_fill(
+                painter,
+                &exterior,
+                &interiors,
+                overlay.color.gamma_multiply(0.3),
+            );
+            for ring in std::iter::once(&exterior).chain(&interiors) {
+                painter.add(egui::Shape::closed_line(ring.clone(), stroke));
+            }
+        }
+        OverlayShape::DirectedLine(line) => {
+            let points: Vec<Pos2> = line.coords().filter_map(to_screen).collect();
+            painter.add(egui::Shape::line(points.clone(), stroke));
+            for segment in points.windows(2) {
+                paint_arrowhead(painter, segment[0], segment[1], overlay.color);
+            }
+        }
+        OverlayShape::Compass { position, bearing } => {
+            // North on screen is found by projecting a point a little further north.
+            let north_of = |coord: &Coord<f64>| geo::coord! { x: coord.x, y: coord.y + 1e-4 };
+            let (Some(center), Some(north)) = (to_screen(position), to_screen(&north_of(position)))
+            else {
+                return;
+            };
+            let north = (north - center).normalized();
+            if !north.x.is_finite() || !north.y.is_finite() {
+                return;
+            }
+            paint_compass(painter, center, north, *bearing, overlay.color);
+        }
+        OverlayShape::PointCloud(points) => {
+            for point in points.iter().filter_map(to_screen) {
+                painter.circle_filled(point, 2.0, overlay.color);
+            }
+        }
+        OverlayShape::Segments(segments) => {
+            let stroke = Stroke::new(1.0, overlay.color);
+            for segment in segments {
+                if let (Some(start), Some(end)) =
+                    (to_screen(&segment.start), to_screen(&segment.end))
+                {
+                    painter.line_segment([start, end], stroke);
+                }
+            }
+        }
+    }
+}
+
+/// Draws a ring with a north tick and an arrow along `bearing`. `north` is the screen direction of
+/// north at `center`, as a unit vector.
+fn paint_compass(painter: &Painter, center: Pos2, north: egui::Vec2, bearing: f64, color: Color32) {
+    const RADIUS: f32 = 32.0;
+    let outline = Stroke::new(1.0, Color32::DARK_GRAY);
+    painter.circle_stroke(center, RADIUS, outline);
+    painter.line_segment(
+        [center + north * (RADIUS - 6.0), center + north * RADIUS],
+        outline,
+    );
+    painter.text(
+        center + north * (RADIUS + 8.0),
+        egui::Align2::CENTER_CENTER,
+        "N",
+        egui::FontId::proportional(12.0),
+        Color32::DARK_GRAY,
+    );
+
+    // Bearings turn clockwise, which on screen (y down) is a positive rotation.
+    let direction = egui::emath::Rot2::from_angle(bearing.to_radians() as f32) * north;
+    let tip = center + direction * RADIUS;
+    painter.line_segment([center, tip], Stroke::new(2.0, color));
+    paint_arrowhead_at(painter, tip, direction, color);
+}
+
+const ARROWHEAD_LENGTH: f32 = 9.0;
+
+/// Draws an arrowhead at the middle of the screen segment from `start` to `end`, pointing at `end`.
+/// Segments too short to fit one are left bare.
+fn paint_arrowhead(painter: &Painter, start: Pos2, end: Pos2, color: Color32) {
+    let delta = end - start;
+    if delta.length() < ARROWHEAD_LENGTH * 2.0 {
+        return;
+    }
+    let direction = delta.normalized();
+    let tip = start + delta / 2.0 + direction * (ARROWHEAD_LENGTH / 2.0);
+    paint_arrowhead_at(painter, tip, direction, color);
+}
+
+/// Draws an arrowhead with its point at `tip`, facing along the unit vector `direction`.
+fn paint_arrowhead_at(painter: &Painter, tip: Pos2, direction: egui::Vec2, color: Color32) {
+    const LENGTH: f32 = ARROWHEAD_LENGTH;
+    let back = tip - direction * LENGTH;
+    let side = direction.rot90() * (LENGTH / 2.0);
+    painter.add(egui::Shape::convex_polygon(
+        vec![tip, back + side, back - side],
+        color,
+        Stroke::NONE,
+    ));
+}
+
+/// Fills a possibly concave screen space polygon by triangulating it with earcut, since egui can
+/// only fill convex paths itself.
+fn paint_polygon_fill(
+    painter: &Painter,
+    exterior: &[Pos2],
+    interiors: &[Vec<Pos2>],
+    color: Color32,
+) {
+    use geo::TriangulateEarcut;
+
+    let to_line_string = |ring: &[Pos2]| -> geo::LineString<f64> {
+        ring.iter()
+            .map(|p| geo::coord! { x: p.x as f64, y: p.y as f64 })
+            .collect()
+    };
+    let polygon = geo::Polygon::new(
+        to_line_string(exterior),
+        interiors.iter().map(|ring| to_line_string(ring)).collect(),
+    );
+    let triangulation = polygon.earcut_triangles_raw();
+
+    let mut mesh = egui::Mesh::default();
+    for xy in triangulation.vertices.chunks_exact(2) {
+        mesh.colored_vertex(egui::pos2(xy[0] as f32, xy[1] as f32), color);
+    }
+    for index in triangulation.triangle_indices {
+        mesh.indices.push(index as u32);
+    }
+    painter.add(egui::Shape::mesh(mesh));
+}
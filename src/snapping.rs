@@ -0,0 +1,283 @@
+//! Snapping of dragged vertices to other vertices, segments, a coordinate grid or integer
+//! coordinates. Exactly-touching inputs are where most predicate edge cases live, and they are
+//! impossible to produce with raw mouse positions.
+
+use galileo_types::cartesian::{CartesianPoint2d, Point2};
+use galileo_types::geo::impls::GeoPoint2d;
+use galileo_types::geo::{Crs, GeoPoint, NewGeoPoint};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GridUnit {
+    Degrees,
+    /// Units of the map CRS. For EPSG:3857 these only match meters on the ground at the equator,
+    /// so they are not labeled as meters.
+    MapUnits,
+}
+
+#[derive(Debug, Clone)]
+pub struct SnapSettings {
+    pub to_vertex: bool,
+    pub to_segment: bool,
+    pub to_grid: bool,
+    pub grid_size: f64,
+    pub grid_unit: GridUnit,
+    pub to_integer: bool,
+    /// Search radius, in screen pixels, for vertex and segment snapping.
+    pub tolerance_px: f64,
+}
+
+impl Default for SnapSettings {
+    fn default() -> Self {
+        SnapSettings {
+            to_vertex: false,
+            to_segment: false,
+            to_grid: false,
+            grid_size: 0.1,
+            grid_unit: GridUnit::Degrees,
+            to_integer: false,
+            tolerance_px: 10.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapKind {
+    /// Snapped onto the vertex with this index in the shared points.
+    Vertex(usize),
+    /// Snapped onto the segment between these two vertex indices.
+    Segment(usize, usize),
+    Grid,
+    Integer,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SnapResult {
+    pub position: Point2,
+    pub kind: SnapKind,
+}
+
+/// Snaps `position`, in the map CRS `crs`, according to `settings`.
+///
+/// Candidates are tried in the order vertex, segment, integer, grid, so geometric snapping wins
+/// over coordinate rounding. The vertex being dragged and the segments incident to it are never
+/// snap targets. Returns `None` if no enabled snap mode applies.
+pub fn snap_position(
+    position: Point2,
+    dragged_index: usize,
+    points: &[Point2],
+    segments: &[(usize, usize)],
+    settings: &SnapSettings,
+    resolution: f64,
+    crs: &Crs,
+) -> Option<SnapResult> {
+    let tolerance = settings.tolerance_px * resolution;
+
+    if settings.to_vertex {
+        let nearest_vertex = points
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| *index != dragged_index)
+            .map(|(index, point)| (index, distance(&position, point)))
+            .filter(|(_, dist)| *dist <= tolerance)
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+        if let Some((index, _)) = nearest_vertex {
+            return Some(SnapResult {
+                position: points[index],
+                kind: SnapKind::Vertex(index),
+            });
+        }
+    }
+
+    if settings.to_segment {
+        let nearest_on_segment = segments
+            .iter()
+            .filter(|(start, end)| *start != dragged_index && *end != dragged_index)
+            .filter_map(|&(start, end)| {
+                let projected =
+                    project_onto_segment(&position, points.get(start)?, points.get(end)?);
+                Some((start, end, projected, distance(&position, &projected)))
+            })
+            .filter(|(_, _, _, dist)| *dist <= tolerance)
+            .min_by(|a, b| a.3.total_cmp(&b.3));
+        if let Some((start, end, projected, _)) = nearest_on_segment {
+            return Some(SnapResult {
+                position: projected,
+                kind: SnapKind::Segment(start, end),
+            });
+        }
+    }
+
+    if settings.to_integer {
+        if let Some(snapped) = round_lonlat(&position, 1.0, crs) {
+            return Some(SnapResult {
+                position: snapped,
+                kind: SnapKind::Integer,
+            });
+        }
+    }
+
+    if settings.to_grid && settings.grid_size > 0.0 {
+        let snapped = match settings.grid_unit {
+            GridUnit::Degrees => round_lonlat(&position, settings.grid_size, crs),
+            GridUnit::MapUnits => Some(Point2::new(
+                round_to(position.x(), settings.grid_size),
+                round_to(position.y(), settings.grid_size),
+            )),
+        };
+        if let Some(snapped) = snapped {
+            return Some(SnapResult {
+                position: snapped,
+                kind: SnapKind::Grid,
+            });
+        }
+    }
+
+    None
+}
+
+fn distance(a: &Point2, b: &Point2) -> f64 {
+    (a.x() - b.x()).hypot(a.y() - b.y())
+}
+
+fn project_onto_segment(point: &Point2, start: &Point2, end: &Point2) -> Point2 {
+    let (dx, dy) = (end.x() - start.x(), end.y() - start.y());
+    let length_squared = dx * dx + dy * dy;
+    if length_squared == 0.0 {
+        return *start;
+    }
+    let t = (((point.x() - start.x()) * dx + (point.y() - start.y()) * dy) / length_squared)
+        .clamp(0.0, 1.0);
+    Point2::new(start.x() + t * dx, start.y() + t * dy)
+}
+
+fn round_to(value: f64, step: f64) -> f64 {
+    (value / step).round() * step
+}
+
+/// Rounds the WGS84 coordinates of a point in `crs` to multiples of `step` degrees.
+fn round_lonlat(position: &Point2, step: f64, crs: &Crs) -> Option<Point2> {
+    let projection = crs.get_projection::<GeoPoint2d, Point2>()?;
+    let geo_point = projection.unproject(position)?;
+    let rounded = GeoPoint2d::lonlat(
+        round_to(geo_point.lon(), step),
+        round_to(geo_point.lat(), step),
+    );
+    projection.project(&rounded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RESOLUTION: f64 = 1.0;
+
+    fn settings() -> SnapSettings {
+        SnapSettings {
+            to_vertex: true,
+            to_segment: true,
+            to_grid: true,
+            grid_size: 100.0,
+            grid_unit: GridUnit::MapUnits,
+            to_integer: false,
+            tolerance_px: 10.0,
+        }
+    }
+
+    fn project(lon: f64, lat: f64) -> Point2 {
+        Crs::EPSG3857
+            .get_projection::<GeoPoint2d, Point2>()
+            .unwrap()
+            .project(&GeoPoint2d::lonlat(lon, lat))
+            .unwrap()
+    }
+
+    fn assert_point_close(actual: Point2, expected: Point2) {
+        assert!(
+            distance(&actual, &expected) < 1e-6,
+            "{:?} is not {:?}",
+            actual,
+            expected
+        );
+    }
+
+    /// The dragged vertex, a vertex at the origin and a segment from it along the x axis.
+    fn points() -> Vec<Point2> {
+        vec![
+            Point2::new(4.0, 2.0),
+            Point2::new(0.0, 0.0),
+            Point2::new(1000.0, 0.0),
+        ]
+    }
+
+    fn snap(position: Point2, settings: &SnapSettings) -> Option<SnapResult> {
+        snap_position(
+            position,
+            0,
+            &points(),
+            &[(1, 2)],
+            settings,
+            RESOLUTION,
+            &Crs::EPSG3857,
+        )
+    }
+
+    #[test]
+    fn vertex_wins_over_segment_and_grid() {
+        // The dragged vertex itself is the closest point, but is never a target.
+        let snapped = snap(Point2::new(4.0, 3.0), &settings()).unwrap();
+        assert_eq!(snapped.kind, SnapKind::Vertex(1));
+        assert_point_close(snapped.position, Point2::new(0.0, 0.0));
+    }
+
+    #[test]
+    fn segment_wins_over_grid() {
+        let snapped = snap(Point2::new(512.0, 4.0), &settings()).unwrap();
+        assert_eq!(snapped.kind, SnapKind::Segment(1, 2));
+        assert_point_close(snapped.position, Point2::new(512.0, 0.0));
+    }
+
+    #[test]
+    fn integer_wins_over_grid() {
+        let settings = SnapSettings {
+            to_vertex: false,
+            to_segment: false,
+            to_integer: true,
+            grid_unit: GridUnit::Degrees,
+            grid_size: 0.5,
+            ..settings()
+        };
+        let snapped = snap(project(10.3, 20.7), &settings).unwrap();
+        assert_eq!(snapped.kind, SnapKind::Integer);
+        assert_point_close(snapped.position, project(10.0, 21.0));
+    }
+
+    #[test]
+    fn grid_in_degrees_rounds_lonlat() {
+        let settings = SnapSettings {
+            grid_unit: GridUnit::Degrees,
+            grid_size: 0.5,
+            ..settings()
+        };
+        let snapped = snap(project(10.34, 20.66), &settings).unwrap();
+        assert_eq!(snapped.kind, SnapKind::Grid);
+        assert_point_close(snapped.position, project(10.5, 20.5));
+    }
+
+    #[test]
+    fn grid_in_map_units_rounds_projected_coordinates() {
+        let snapped = snap(Point2::new(1234.0, -5678.0), &settings()).unwrap();
+        assert_eq!(snapped.kind, SnapKind::Grid);
+        assert_point_close(snapped.position, Point2::new(1200.0, -5700.0));
+    }
+
+    #[test]
+    fn nothing_enabled_does_not_snap() {
+        let settings = SnapSettings {
+            to_vertex: false,
+            to_segment: false,
+            to_grid: false,
+            ..settings()
+        };
+        assert!(snap(Point2::new(3.0, 1.0), &settings).is_none());
+    }
+}